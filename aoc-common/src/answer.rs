@@ -0,0 +1,63 @@
+use std::fmt::Display;
+
+/// The result of solving one part of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(i64),
+    Text(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{n}"),
+            Answer::Text(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+/// Numbers that don't fit in an `i64` are kept exactly as text rather than wrapped
+macro_rules! impl_from_number {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    i64::try_from(value)
+                        .map_or_else(|_| Answer::Text(value.to_string()), Answer::Number)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_number!(i32, i64, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::from(42_usize).to_string(), "42");
+        assert_eq!(Answer::from(-3).to_string(), "-3");
+        assert_eq!(Answer::from("MCD").to_string(), "MCD");
+    }
+
+    #[test]
+    fn test_large_numbers_are_not_wrapped() {
+        assert_eq!(Answer::from(i64::MAX as u64), Answer::Number(i64::MAX));
+        assert_eq!(Answer::from(u64::MAX).to_string(), "18446744073709551615");
+    }
+}
@@ -0,0 +1,116 @@
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{
+    eyre::{eyre, Result, WrapErr},
+    Section,
+};
+
+/// Where a day's puzzle input is read from at runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    Path(PathBuf),
+    Stdin,
+    /// A directory holding one `dayNN.txt` file per day
+    CacheDir(PathBuf),
+}
+
+impl InputSource {
+    /// Reads input from the per-user cache, e.g. `~/.cache/aoc/2022/day07.txt`.
+    ///
+    /// `AOC_CACHE_DIR` overrides the location.
+    pub fn default_cache() -> Result<Self> {
+        if let Some(dir) = std::env::var_os("AOC_CACHE_DIR") {
+            return Ok(InputSource::CacheDir(dir.into()));
+        }
+
+        dirs::cache_dir()
+            .map(|dir| InputSource::CacheDir(dir.join("aoc").join("2022")))
+            .ok_or_else(|| eyre!("Could not determine the cache directory"))
+            .suggestion("Set AOC_CACHE_DIR or pass --input")
+    }
+
+    pub fn load(&self, day: u8) -> Result<String> {
+        match self {
+            InputSource::Path(path) => read_file(path),
+            InputSource::Stdin => {
+                let mut input = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut input)
+                    .wrap_err("Failed to read input from stdin")?;
+                Ok(input)
+            }
+            InputSource::CacheDir(dir) => {
+                let path = cache_path(dir, day);
+                if !path.exists() {
+                    return Err(eyre!("No cached input for day {day} at {}", path.display()))
+                        .suggestion(
+                        "Save your puzzle input there, or pass --input <path> (`-` reads stdin)",
+                    );
+                }
+                read_file(&path)
+            }
+        }
+    }
+}
+
+impl From<&str> for InputSource {
+    /// `-` means stdin, anything else is a path
+    fn from(value: &str) -> Self {
+        match value {
+            "-" => InputSource::Stdin,
+            path => InputSource::Path(path.into()),
+        }
+    }
+}
+
+pub fn cache_path(dir: &Path, day: u8) -> PathBuf {
+    dir.join(format!("day{day:02}.txt"))
+}
+
+fn read_file(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path)
+        .wrap_err_with(|| format!("Failed to read input file {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_path() {
+        let path = cache_path(Path::new("/cache"), 7);
+        assert_eq!(path, PathBuf::from("/cache/day07.txt"));
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(InputSource::from("-"), InputSource::Stdin);
+        assert_eq!(
+            InputSource::from("day07.txt"),
+            InputSource::Path("day07.txt".into())
+        );
+    }
+
+    #[test]
+    fn test_load_from_cache_dir() {
+        let dir = std::env::temp_dir().join(format!("aoc-input-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("day07.txt"), "$ cd /\n").unwrap();
+
+        let input = InputSource::CacheDir(dir.clone()).load(7);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(input.unwrap(), "$ cd /\n");
+    }
+
+    #[test]
+    fn test_missing_cached_input() {
+        let err = InputSource::CacheDir("/nonexistent".into())
+            .load(7)
+            .unwrap_err();
+        assert!(err.to_string().contains("/nonexistent/day07.txt"));
+    }
+}
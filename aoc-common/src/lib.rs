@@ -0,0 +1,7 @@
+mod answer;
+pub mod input;
+pub mod parse;
+mod solution;
+
+pub use answer::Answer;
+pub use solution::{Part, Solution};
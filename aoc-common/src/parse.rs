@@ -0,0 +1,170 @@
+use std::fmt::Display;
+
+use color_eyre::{Section, SectionExt};
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+/// Bad puzzle input, pointing at the offending part of the line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based, or 0 while the line isn't known yet
+    pub line: usize,
+    /// 1-based, counted in chars
+    pub column: usize,
+    pub snippet: String,
+    pub expected: String,
+    source_line: String,
+}
+
+impl ParseError {
+    /// An error for `snippet`, which must be a slice of `source_line`.
+    ///
+    /// An empty `snippet` that isn't part of `source_line` points past the end of the line.
+    pub fn at(source_line: &str, snippet: &str, expected: impl Into<String>) -> Self {
+        Self {
+            line: 0,
+            column: column_of(source_line, snippet),
+            snippet: snippet.to_owned(),
+            expected: expected.into(),
+            source_line: source_line.to_owned(),
+        }
+    }
+
+    pub fn with_line(mut self, line: usize) -> Self {
+        self.line = line;
+        self
+    }
+
+    /// Re-anchors an error raised while parsing `segment` onto the full `line` it was cut from.
+    pub fn within(mut self, line: &str, segment: &str) -> Self {
+        self.column += column_of(line, segment) - 1;
+        self.source_line = line.to_owned();
+        self
+    }
+
+    /// The offending line with the snippet underlined, behind a line-number gutter once the
+    /// line is known.
+    pub fn excerpt(&self) -> String {
+        let (numbered, gutter) = match self.line {
+            0 => (String::new(), String::new()),
+            line => (
+                format!("{line} | "),
+                format!("{} | ", " ".repeat(line.to_string().len())),
+            ),
+        };
+        let underline = "^".repeat(self.snippet.chars().count().max(1));
+
+        format!(
+            "{numbered}{}\n{gutter}{}{underline}",
+            self.source_line,
+            " ".repeat(self.column - 1),
+        )
+    }
+
+    /// Converts into a report that shows the source excerpt below the error.
+    pub fn into_report(self) -> color_eyre::Report {
+        let excerpt = self.excerpt();
+        color_eyre::Report::new(self).section(excerpt.header("Input:"))
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.line > 0 {
+            write!(f, "line {}, ", self.line)?;
+        }
+        write!(f, "column {}: expected {}, ", self.column, self.expected)?;
+
+        if self.snippet.is_empty() {
+            write!(f, "found end of line")
+        } else {
+            write!(f, "found {:?}", self.snippet)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn column_of(line: &str, snippet: &str) -> usize {
+    let offset = (snippet.as_ptr() as usize)
+        .checked_sub(line.as_ptr() as usize)
+        .and_then(|offset| line.get(..offset))
+        .unwrap_or(line);
+
+    offset.chars().count() + 1
+}
+
+/// Parses each line with `parse`, filling in the line number of any error.
+pub fn parse_lines<'a, T>(
+    input: &'a str,
+    mut parse: impl FnMut(&'a str) -> ParseResult<T>,
+) -> ParseResult<Vec<T>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parse(line).map_err(|err| err.with_line(i + 1)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_from_snippet() {
+        let line = "addx 1x";
+        let err = ParseError::at(line, &line[5..], "a number").with_line(3);
+
+        assert_eq!(err.column, 6);
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 6: expected a number, found \"1x\""
+        );
+        assert_eq!(err.excerpt(), "3 | addx 1x\n  |      ^^");
+    }
+
+    #[test]
+    fn test_missing_snippet_points_past_end() {
+        let err = ParseError::at("addx", "", "a number").with_line(1);
+
+        assert_eq!(err.column, 5);
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 5: expected a number, found end of line"
+        );
+    }
+
+    #[test]
+    fn test_within() {
+        let line = "1-3,5-x";
+        let segment = &line[4..];
+        let err = ParseError::at(segment, &segment[2..], "a number").within(line, segment);
+
+        assert_eq!(err.column, 7);
+        assert_eq!(err.excerpt(), "1-3,5-x\n      ^");
+
+        let err = err.with_line(2);
+        assert_eq!(err.excerpt(), "2 | 1-3,5-x\n  |       ^");
+    }
+
+    #[test]
+    fn test_unknown_line_is_left_out() {
+        let err = ParseError::at("5", "5", "a section range");
+        assert_eq!(
+            err.to_string(),
+            "column 1: expected a section range, found \"5\""
+        );
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let err = parse_lines("1\n2\nx", |line| {
+            line.parse::<u32>()
+                .map_err(|_| ParseError::at(line, line, "a number"))
+        })
+        .unwrap_err();
+
+        assert_eq!(err.line, 3);
+        assert_eq!(err.column, 1);
+    }
+}
@@ -0,0 +1,64 @@
+use std::fmt::Display;
+
+use color_eyre::eyre::{eyre, Result};
+
+use crate::Answer;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl TryFrom<u8> for Part {
+    type Error = color_eyre::Report;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            _ => Err(eyre!("Invalid part {value}, expected 1 or 2")),
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+/// A single day's puzzle.
+///
+/// The input is parsed once and both parts are answered from the parsed form, so tooling can
+/// call every day the same way.
+pub trait Solution {
+    const DAY: u8;
+
+    type Parsed;
+
+    fn parse(input: &str) -> Result<Self::Parsed>;
+
+    fn part1(parsed: &Self::Parsed) -> Result<Answer>;
+
+    fn part2(parsed: &Self::Parsed) -> Result<Answer>;
+
+    /// Problems in the input that didn't stop parsing, e.g. skipped lines
+    fn warnings(_parsed: &Self::Parsed) -> Vec<String> {
+        vec![]
+    }
+
+    fn solve(parsed: &Self::Parsed, part: Part) -> Result<Answer> {
+        match part {
+            Part::One => Self::part1(parsed),
+            Part::Two => Self::part2(parsed),
+        }
+    }
+}
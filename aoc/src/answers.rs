@@ -0,0 +1,107 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use aoc_common::Part;
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use serde::Deserialize;
+
+/// An input file and the answers it is known to produce.
+#[derive(Debug, Deserialize)]
+pub struct Expected {
+    /// Relative to the directory holding the answers file
+    pub path: PathBuf,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl Expected {
+    pub fn answer(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.part1.as_deref(),
+            Part::Two => self.part2.as_deref(),
+        }
+    }
+}
+
+/// Expected answers keyed by day (`day07`), then by input name (`input`, `sample`, ...).
+#[derive(Debug)]
+pub struct Registry {
+    days: BTreeMap<u8, BTreeMap<String, Expected>>,
+}
+
+impl Registry {
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read answers file {}", path.display()))?;
+        let base_dir = path.parent().unwrap_or(Path::new(""));
+
+        Self::parse(&contents, base_dir)
+            .wrap_err_with(|| format!("Invalid answers file {}", path.display()))
+    }
+
+    fn parse(contents: &str, base_dir: &Path) -> Result<Self> {
+        let raw: BTreeMap<String, BTreeMap<String, Expected>> = toml::from_str(contents)?;
+
+        let mut days = BTreeMap::new();
+        for (key, mut inputs) in raw {
+            let day = key
+                .strip_prefix("day")
+                .and_then(|day| day.parse::<u8>().ok())
+                .ok_or_else(|| eyre!("Invalid day key {key:?}, expected e.g. \"day07\""))?;
+
+            for expected in inputs.values_mut() {
+                expected.path = base_dir.join(&expected.path);
+            }
+            days.insert(day, inputs);
+        }
+
+        Ok(Self { days })
+    }
+
+    pub fn inputs(&self, day: u8) -> impl Iterator<Item = (&str, &Expected)> {
+        self.days
+            .get(&day)
+            .into_iter()
+            .flatten()
+            .map(|(name, expected)| (name.as_str(), expected))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let registry = Registry::parse(
+            r#"
+            [day05.sample]
+            path = "day05/src/starting_input.txt"
+            part2 = "MCD"
+            "#,
+            Path::new("/repo"),
+        )
+        .unwrap();
+
+        let inputs = registry.inputs(5).collect::<Vec<_>>();
+        assert_eq!(inputs.len(), 1);
+
+        let (name, expected) = inputs[0];
+        assert_eq!(name, "sample");
+        assert_eq!(
+            expected.path,
+            PathBuf::from("/repo/day05/src/starting_input.txt")
+        );
+        assert_eq!(expected.answer(Part::One), None);
+        assert_eq!(expected.answer(Part::Two), Some("MCD"));
+        assert_eq!(registry.inputs(6).count(), 0);
+    }
+
+    #[test]
+    fn test_invalid_day_key() {
+        let err = Registry::parse("[seven.input]\npath = \"x\"", Path::new("")).unwrap_err();
+        assert!(err.to_string().contains("seven"));
+    }
+}
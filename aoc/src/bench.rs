@@ -0,0 +1,209 @@
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use aoc_common::{Part, Solution};
+use color_eyre::eyre::Result;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    pub warmup: u32,
+    pub iterations: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl From<Part> for Phase {
+    fn from(part: Part) -> Self {
+        match part {
+            Part::One => Phase::Part1,
+            Part::Two => Phase::Part2,
+        }
+    }
+}
+
+impl std::fmt::Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Phase::Parse => write!(f, "parse"),
+            Phase::Part1 => write!(f, "part 1"),
+            Phase::Part2 => write!(f, "part 2"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PhaseTiming {
+    pub phase: Phase,
+    pub min_ns: u128,
+    pub median_ns: u128,
+    pub mean_ns: u128,
+}
+
+impl PhaseTiming {
+    fn from_samples(phase: Phase, mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        let total: Duration = samples.iter().sum();
+
+        Self {
+            phase,
+            min_ns: samples.first().map_or(0, Duration::as_nanos),
+            median_ns: samples.get(samples.len() / 2).map_or(0, Duration::as_nanos),
+            mean_ns: total.as_nanos() / samples.len().max(1) as u128,
+        }
+    }
+}
+
+/// A part that returned an error, so it has no timings
+#[derive(Debug, Serialize)]
+pub struct PhaseFailure {
+    pub phase: Phase,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DayTiming {
+    pub day: u8,
+    pub iterations: u32,
+    pub phases: Vec<PhaseTiming>,
+    pub failures: Vec<PhaseFailure>,
+}
+
+fn time<T>(options: &Options, mut f: impl FnMut() -> T) -> Vec<Duration> {
+    for _ in 0..options.warmup {
+        black_box(f());
+    }
+
+    (0..options.iterations)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect()
+}
+
+/// Times parsing and each part of `S` separately. Parts that fail are recorded
+/// as failures instead of being timed.
+pub fn bench<S: Solution>(input: &str, options: &Options) -> Result<DayTiming> {
+    let parsed = S::parse(input)?;
+    crate::print_warnings::<S>(&parsed);
+    let mut phases = vec![PhaseTiming::from_samples(
+        Phase::Parse,
+        time(options, || S::parse(input)),
+    )];
+
+    let mut failures = vec![];
+    for part in Part::ALL {
+        // One untimed run first, so a failing part isn't timed at all
+        if let Err(err) = S::solve(&parsed, part) {
+            failures.push(PhaseFailure {
+                phase: part.into(),
+                error: err.to_string(),
+            });
+            continue;
+        }
+
+        let samples = time(options, || S::solve(&parsed, part));
+        phases.push(PhaseTiming::from_samples(part.into(), samples));
+    }
+
+    Ok(DayTiming {
+        day: S::DAY,
+        iterations: options.iterations,
+        phases,
+        failures,
+    })
+}
+
+fn format_ns(ns: u128) -> String {
+    format!("{:.1?}", Duration::from_nanos(ns as u64))
+}
+
+pub fn print_table(timings: &[DayTiming]) {
+    println!(
+        "{:>3}  {:<6}  {:>10}  {:>10}  {:>10}",
+        "day", "phase", "min", "median", "mean"
+    );
+
+    for timing in timings {
+        for phase in &timing.phases {
+            println!(
+                "{:>3}  {:<6}  {:>10}  {:>10}  {:>10}",
+                timing.day,
+                phase.phase.to_string(),
+                format_ns(phase.min_ns),
+                format_ns(phase.median_ns),
+                format_ns(phase.mean_ns),
+            );
+        }
+        for failure in &timing.failures {
+            println!(
+                "{:>3}  {:<6}  failed: {}",
+                timing.day,
+                failure.phase.to_string(),
+                failure.error
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phase_timing_from_samples() {
+        let samples = [3, 1, 2, 10].map(Duration::from_nanos).to_vec();
+        let timing = PhaseTiming::from_samples(Phase::Parse, samples);
+
+        assert_eq!(timing.min_ns, 1);
+        assert_eq!(timing.median_ns, 3);
+        assert_eq!(timing.mean_ns, 4);
+    }
+
+    struct PartOneOnly;
+
+    impl Solution for PartOneOnly {
+        const DAY: u8 = 1;
+
+        type Parsed = usize;
+
+        fn parse(input: &str) -> Result<Self::Parsed> {
+            Ok(input.len())
+        }
+
+        fn part1(len: &Self::Parsed) -> Result<aoc_common::Answer> {
+            Ok((*len).into())
+        }
+
+        fn part2(_: &Self::Parsed) -> Result<aoc_common::Answer> {
+            Err(color_eyre::eyre::eyre!("not implemented"))
+        }
+    }
+
+    #[test]
+    fn test_bench_records_unsolved_parts() {
+        let options = Options {
+            warmup: 1,
+            iterations: 3,
+        };
+        let timing = bench::<PartOneOnly>("input", &options).unwrap();
+
+        let phases = timing.phases.iter().map(|p| p.phase).collect::<Vec<_>>();
+        assert_eq!(timing.iterations, 3);
+        assert_eq!(phases, vec![Phase::Parse, Phase::Part1]);
+
+        let failures = timing.failures.iter().map(|f| f.phase).collect::<Vec<_>>();
+        assert_eq!(failures, vec![Phase::Part2]);
+        assert_eq!(timing.failures[0].error, "not implemented");
+    }
+}
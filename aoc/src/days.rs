@@ -0,0 +1,24 @@
+use std::ops::RangeInclusive;
+
+pub const ALL: RangeInclusive<u8> = 1..=10;
+
+/// Calls `func::<S>(args...)` with `S` being the `Solution` implementation for `$day`.
+macro_rules! dispatch {
+    ($day:expr, $($func:ident)::+($($arg:expr),* $(,)?)) => {
+        match $day {
+            1 => $($func)::+::<day1::Day01>($($arg),*),
+            2 => $($func)::+::<day2::Day02>($($arg),*),
+            3 => $($func)::+::<day3::Day03>($($arg),*),
+            4 => $($func)::+::<day4::Day04>($($arg),*),
+            5 => $($func)::+::<day5::Day05>($($arg),*),
+            6 => $($func)::+::<day6::Day06>($($arg),*),
+            7 => $($func)::+::<day7::Day07>($($arg),*),
+            8 => $($func)::+::<day8::Day08>($($arg),*),
+            9 => $($func)::+::<day9::Day09>($($arg),*),
+            10 => $($func)::+::<day10::Day10>($($arg),*),
+            day => Err(color_eyre::eyre::eyre!("Day {day} has no solution")),
+        }
+    };
+}
+
+pub(crate) use dispatch;
@@ -0,0 +1,247 @@
+use std::path::PathBuf;
+
+use aoc_common::{input::InputSource, Answer, Part, Solution};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use color_eyre::eyre::{eyre, Result, WrapErr};
+
+mod answers;
+mod bench;
+mod days;
+mod verify;
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Advent of Code 2022 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a day's solver against its puzzle input
+    Run {
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=10))]
+        day: u8,
+        /// Only run this part; runs both when omitted
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Time parsing and each part of every day
+    Bench {
+        /// Only benchmark this day
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=10))]
+        day: Option<u8>,
+        #[command(flatten)]
+        input: InputArgs,
+        #[arg(long, default_value_t = 10)]
+        warmup: u32,
+        #[arg(long, default_value_t = 100)]
+        iterations: u32,
+        /// Also write the timings as JSON to this file
+        #[arg(long)]
+        json: Option<PathBuf>,
+    },
+    /// Check every day's answers against the answers file
+    Verify {
+        #[arg(long, default_value = "answers.toml")]
+        answers: PathBuf,
+        /// Only verify this day
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=10))]
+        day: Option<u8>,
+    },
+    /// Show the filesystem reconstructed from a day 7 transcript
+    Fs {
+        #[arg(long, value_enum, default_value_t = FsFormat::Tree)]
+        format: FsFormat,
+        /// Only show entries this many levels below `/`
+        #[arg(long)]
+        max_depth: Option<usize>,
+        /// Print sizes like `47M` instead of raw byte counts
+        #[arg(short = 'H', long)]
+        human_readable: bool,
+        /// Transcript file, or `-` for stdin; defaults to the cached input for day 7
+        #[arg(long)]
+        input: Option<String>,
+        /// Directory holding `dayNN.txt` inputs [default: ~/.cache/aoc/2022, or $AOC_CACHE_DIR]
+        #[arg(long, conflicts_with = "input")]
+        cache_dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum FsFormat {
+    /// One line per directory, like `du`
+    Du,
+    /// Every entry as an indented tree
+    Tree,
+    /// The whole tree with sizes as JSON
+    Json,
+}
+
+#[derive(Args)]
+struct InputArgs {
+    /// Input file, or `-` for stdin; defaults to the cached input for the day
+    #[arg(long, requires = "day")]
+    input: Option<String>,
+    /// Directory holding `dayNN.txt` inputs [default: ~/.cache/aoc/2022, or $AOC_CACHE_DIR]
+    #[arg(long, conflicts_with = "input")]
+    cache_dir: Option<PathBuf>,
+}
+
+impl InputArgs {
+    fn source(&self) -> Result<InputSource> {
+        match (&self.input, &self.cache_dir) {
+            (Some(input), _) => Ok(InputSource::from(input.as_str())),
+            (None, Some(dir)) => Ok(InputSource::CacheDir(dir.clone())),
+            (None, None) => InputSource::default_cache(),
+        }
+    }
+}
+
+fn print_answer(day: u8, part: Part, answer: &Answer) {
+    let answer = answer.to_string();
+    if answer.contains('\n') {
+        println!("Day {day} part {part}:\n{answer}");
+    } else {
+        println!("Day {day} part {part}: {answer}");
+    }
+}
+
+fn print_warnings<S: Solution>(parsed: &S::Parsed) {
+    for warning in S::warnings(parsed) {
+        eprintln!("warning: day {}: {warning}", S::DAY);
+    }
+}
+
+fn run<S: Solution>(input: &str, part: Option<Part>) -> Result<()> {
+    let parsed = S::parse(input)?;
+    print_warnings::<S>(&parsed);
+
+    if let Some(part) = part {
+        let answer = S::solve(&parsed, part)?;
+        print_answer(S::DAY, part, &answer);
+        return Ok(());
+    }
+
+    // Running both parts shouldn't stop at the first part that's missing
+    for part in Part::ALL {
+        match S::solve(&parsed, part) {
+            Ok(answer) => print_answer(S::DAY, part, &answer),
+            Err(err) => eprintln!("{err}"),
+        }
+    }
+
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Run { day, part, input } => {
+            let input = input.source()?.load(day)?;
+            let part = part.map(Part::try_from).transpose()?;
+
+            days::dispatch!(day, run(&input, part))?;
+        }
+        Command::Bench {
+            day,
+            input,
+            warmup,
+            iterations,
+            json,
+        } => {
+            let options = bench::Options { warmup, iterations };
+            let source = input.source()?;
+            let days = match day {
+                Some(day) => day..=day,
+                None => days::ALL,
+            };
+
+            let mut timings = vec![];
+            for day in days.clone() {
+                let input = match source.load(day) {
+                    Ok(input) => input,
+                    // When benchmarking everything, run whichever days we have input for
+                    Err(err) if days.start() != days.end() => {
+                        eprintln!("Skipping day {day}: {err}");
+                        continue;
+                    }
+                    Err(err) => return Err(err),
+                };
+                timings.push(days::dispatch!(day, bench::bench(&input, &options))?);
+            }
+
+            bench::print_table(&timings);
+
+            if let Some(path) = json {
+                std::fs::write(&path, serde_json::to_string_pretty(&timings)?)
+                    .wrap_err_with(|| format!("Failed to write {}", path.display()))?;
+            }
+        }
+        Command::Verify { answers, day } => {
+            let registry = answers::Registry::load(&answers)?;
+            let days = match day {
+                Some(day) => day..=day,
+                None => days::ALL,
+            };
+
+            let mut outcomes = vec![];
+            for day in days {
+                let day_outcomes = days::dispatch!(day, verify::verify(&registry))?;
+                if day_outcomes.is_empty() {
+                    println!("day {day:>2}  no registered inputs");
+                }
+
+                for outcome in &day_outcomes {
+                    println!(
+                        "day {:>2}  {:<8} part {}  {}",
+                        outcome.day, outcome.input, outcome.part, outcome.status
+                    );
+                }
+                outcomes.extend(day_outcomes);
+            }
+
+            let failures = outcomes.iter().filter(|o| o.status.is_failure()).count();
+            let passes = outcomes
+                .iter()
+                .filter(|o| o.status == verify::Status::Pass)
+                .count();
+            println!(
+                "\n{passes} passed, {failures} failed, {} missing",
+                outcomes.len() - passes - failures
+            );
+
+            if failures > 0 {
+                return Err(eyre!("{failures} answers did not match the answers file"));
+            }
+        }
+        Command::Fs {
+            format,
+            max_depth,
+            human_readable,
+            input,
+            cache_dir,
+        } => {
+            let input = InputArgs { input, cache_dir }.source()?.load(7)?;
+            let filesystem = day7::Filesystem::from_transcript(&input)?;
+            print_warnings::<day7::Day07>(&filesystem);
+
+            let options = day7::report::ReportOptions {
+                max_depth,
+                human_readable,
+            };
+            let output = match format {
+                FsFormat::Du => day7::report::du(&filesystem, &options),
+                FsFormat::Tree => day7::report::tree(&filesystem, &options),
+                FsFormat::Json => day7::report::to_json(&filesystem)?,
+            };
+            println!("{output}");
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use std::fmt::Display;
+
+use aoc_common::{input::InputSource, Part, Solution};
+use color_eyre::eyre::Result;
+
+use crate::answers::Registry;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail {
+        expected: String,
+        actual: String,
+    },
+    /// No answer is registered for this part
+    Missing,
+    Error(String),
+}
+
+impl Status {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Status::Fail { .. } | Status::Error(_))
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail { expected, actual } => {
+                write!(f, "FAIL: expected {expected:?}, got {actual:?}")
+            }
+            Status::Missing => write!(f, "missing"),
+            Status::Error(err) => write!(f, "ERROR: {err}"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Outcome {
+    pub day: u8,
+    pub input: String,
+    pub part: Part,
+    pub status: Status,
+}
+
+fn check(expected: Option<&str>, actual: Result<String, String>) -> Status {
+    let Some(expected) = expected else {
+        return Status::Missing;
+    };
+
+    match actual {
+        Ok(actual) if expected.trim_end() == actual.trim_end() => Status::Pass,
+        Ok(actual) => Status::Fail {
+            expected: expected.to_owned(),
+            actual,
+        },
+        Err(err) => Status::Error(err),
+    }
+}
+
+/// Runs `S` against every input registered for its day and compares the answers.
+pub fn verify<S: Solution>(registry: &Registry) -> Result<Vec<Outcome>> {
+    let mut outcomes = vec![];
+
+    for (name, expected) in registry.inputs(S::DAY) {
+        let parsed = InputSource::Path(expected.path.clone())
+            .load(S::DAY)
+            .and_then(|input| S::parse(&input))
+            .map_err(|err| err.to_string());
+
+        for part in Part::ALL {
+            let actual = match &parsed {
+                Ok(parsed) => S::solve(parsed, part)
+                    .map(|answer| answer.to_string())
+                    .map_err(|err| err.to_string()),
+                Err(err) => Err(err.clone()),
+            };
+
+            outcomes.push(Outcome {
+                day: S::DAY,
+                input: name.to_owned(),
+                part,
+                status: check(expected.answer(part), actual),
+            });
+        }
+    }
+
+    Ok(outcomes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        assert_eq!(check(Some("42"), Ok("42".into())), Status::Pass);
+        assert_eq!(check(Some("CRT\n"), Ok("CRT".into())), Status::Pass);
+        assert_eq!(
+            check(Some("42"), Ok("41".into())),
+            Status::Fail {
+                expected: "42".into(),
+                actual: "41".into()
+            }
+        );
+        assert_eq!(check(None, Ok("42".into())), Status::Missing);
+        assert_eq!(check(None, Err("not implemented".into())), Status::Missing);
+        assert!(check(Some("42"), Err("boom".into())).is_failure());
+    }
+}
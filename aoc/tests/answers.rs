@@ -0,0 +1,16 @@
+use std::process::Command;
+
+#[test]
+fn test_answers_match_registry() {
+    let answers = concat!(env!("CARGO_MANIFEST_DIR"), "/../answers.toml");
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .args(["verify", "--answers", answers])
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+}
@@ -1,17 +1,24 @@
+use aoc_common::{
+    parse::{parse_lines, ParseError, ParseResult},
+    Answer, Solution,
+};
 use itertools::Itertools;
 use std::cmp::Reverse;
 
-pub fn load_input_file() -> &'static str {
-    let input = include_str!("day1.txt");
-    input
-}
-pub fn parse_string_to_elves(input: &str) -> Vec<u32> {
-    // Newlines on Windows are \r\n
-    let lines = input.lines();
+pub fn parse_string_to_elves(input: &str) -> ParseResult<Vec<u32>> {
+    // Newlines on Windows are \r\n, which `lines` handles for us.
+    // Blank lines separate elves and become None.
+    let calories = parse_lines(input, |line| {
+        if line.is_empty() {
+            return Ok(None);
+        }
+        line.parse::<u32>()
+            .map(Some)
+            .map_err(|_| ParseError::at(line, line, "a calorie count"))
+    })?;
 
-    // Remove the None values
-    lines
-        .map(|v| v.parse::<u32>().ok())
+    let elves = calories
+        .into_iter()
         .batching(|l| {
             let mut sum = None;
             while let Some(Some(v)) = l.next() {
@@ -19,10 +26,12 @@ pub fn parse_string_to_elves(input: &str) -> Vec<u32> {
             }
             sum
         })
-        .collect()
+        .collect();
+
+    Ok(elves)
 }
 
-pub fn get_sum_of_top_n(input: Vec<i32>, top_n: usize) -> i32 {
+pub fn get_sum_of_top_n(input: Vec<u32>, top_n: usize) -> u32 {
     input
         .into_iter()
         .map(Reverse)
@@ -31,23 +40,50 @@ pub fn get_sum_of_top_n(input: Vec<i32>, top_n: usize) -> i32 {
         .sum()
 }
 
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+
+    type Parsed = Vec<u32>;
+
+    fn parse(input: &str) -> color_eyre::Result<Self::Parsed> {
+        parse_string_to_elves(input).map_err(ParseError::into_report)
+    }
+
+    fn part1(elves: &Self::Parsed) -> color_eyre::Result<Answer> {
+        Ok(get_sum_of_top_n(elves.clone(), 1).into())
+    }
+
+    fn part2(elves: &Self::Parsed) -> color_eyre::Result<Answer> {
+        Ok(get_sum_of_top_n(elves.clone(), 3).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::load_input_file;
+    use aoc_common::input::InputSource;
 
     #[test]
     fn test_load_file() {
-        load_input_file();
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/src/day1.txt");
+        InputSource::from(path).load(1).unwrap();
     }
 
     #[test]
     fn test_parse_string_to_elves() {
         let input = "1\n2\n\n4\n\n";
-        let actual = super::parse_string_to_elves(input);
+        let actual = super::parse_string_to_elves(input).unwrap();
         let expected = vec![3, 4];
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn test_parse_invalid_calories() {
+        let err = super::parse_string_to_elves("1\n2x\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+    }
+
     #[test]
     fn test_get_sum_of_top_n() {
         let input = vec![1, 2, 3];
@@ -1,13 +1,15 @@
 #[cfg(test)]
 mod main_tests {
-    use day1_lib::{get_sum_of_top_n, load_input_file, parse_string_to_elves};
+    use aoc_common::input::InputSource;
+    use day1::{get_sum_of_top_n, parse_string_to_elves};
 
     #[test]
     fn main() {
         // Load file in day1.txt
-        let input = load_input_file();
-        let mut parsed_elves = parse_string_to_elves(&input);
-        let combined_cals_from_richest_elves = get_sum_of_top_n(&mut parsed_elves, 4);
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/src/day1.txt");
+        let input = InputSource::from(path).load(1).unwrap();
+        let parsed_elves = parse_string_to_elves(&input).unwrap();
+        let combined_cals_from_richest_elves = get_sum_of_top_n(parsed_elves, 4);
 
         // Write result to day1_result.txt
         println!(
@@ -13,9 +13,11 @@
     Get sum of points if everything goes according to the strategy guide.
 */
 
-pub fn load_input() -> &'static str {
-    include_str!("day2.txt")
-}
+use aoc_common::{
+    parse::{parse_lines, ParseError, ParseResult},
+    Answer, Solution,
+};
+use color_eyre::eyre::eyre;
 
 #[derive(Debug, Clone)]
 pub struct Round {
@@ -68,6 +70,16 @@ impl Move {
             Move::Scissors => Move::Rock,
         }
     }
+
+    /// Our move, when the second column of the strategy guide is read as a shape
+    fn from_response(value: char) -> color_eyre::Result<Self> {
+        match value {
+            'X' => Ok(Move::Rock),
+            'Y' => Ok(Move::Paper),
+            'Z' => Ok(Move::Scissors),
+            _ => Err(eyre!("Invalid move")),
+        }
+    }
 }
 
 impl TryFrom<char> for Move {
@@ -120,10 +132,10 @@ impl TryFrom<char> for DesiredResult {
     }
 }
 
-pub fn parse_input_to_moves(input: &str) -> Vec<Round> {
+pub fn parse_input_to_moves(input: &str) -> ParseResult<Vec<Round>> {
     // Load input
-    let char_pairs = str_to_char_pairs(input);
-    char_pairs_to_round(char_pairs)
+    let char_pairs = str_to_char_pairs(input)?;
+    Ok(char_pairs_to_round(char_pairs))
 }
 
 fn char_pairs_to_round(char_pairs: Vec<(&str, &str)>) -> Vec<Round> {
@@ -140,42 +152,96 @@ fn char_pairs_to_round(char_pairs: Vec<(&str, &str)>) -> Vec<Round> {
         .collect::<Vec<_>>()
 }
 
-fn str_to_char_pairs(input: &str) -> Vec<(&str, &str)> {
-    input
-        .lines()
-        .map(|line| {
-            if let Some((first, second)) = line.split_once(' ') {
-                (first, second)
-            } else {
-                panic!("Invalid input");
-            }
-        })
-        .collect::<Vec<_>>()
+fn str_to_char_pairs(input: &str) -> ParseResult<Vec<(&str, &str)>> {
+    parse_lines(input, |line| {
+        let Some((first, second)) = line.split_once(' ') else {
+            return Err(ParseError::at(line, "", "a space between the columns"));
+        };
+
+        if !matches!(first, "A" | "B" | "C") {
+            return Err(ParseError::at(line, first, "A, B or C"));
+        }
+        if !matches!(second, "X" | "Y" | "Z") {
+            return Err(ParseError::at(line, second, "X, Y or Z"));
+        }
+
+        Ok((first, second))
+    })
 }
 
-pub fn lib_main(input: &str) {
-    let moves = parse_input_to_moves(input);
-    let round_points = moves.iter().map(|round| round.points()).collect::<Vec<_>>();
+pub struct Day02;
 
-    let total_points: u32 = round_points.iter().sum();
+impl Solution for Day02 {
+    const DAY: u8 = 2;
 
-    println!("{:?}", total_points)
+    type Parsed = Vec<(char, char)>;
+
+    fn parse(input: &str) -> color_eyre::Result<Self::Parsed> {
+        let pairs = str_to_char_pairs(input).map_err(ParseError::into_report)?;
+
+        // Both columns are validated to be a single char
+        Ok(pairs
+            .iter()
+            .map(|(first, second)| (first.as_bytes()[0] as char, second.as_bytes()[0] as char))
+            .collect())
+    }
+
+    fn part1(pairs: &Self::Parsed) -> color_eyre::Result<Answer> {
+        let total_points = pairs
+            .iter()
+            .map(|&(their_char, our_char)| {
+                let round = Round {
+                    ours: Move::from_response(our_char)?,
+                    theirs: Move::try_from(their_char)?,
+                };
+                Ok(round.points())
+            })
+            .sum::<color_eyre::Result<u32>>()?;
+
+        Ok(total_points.into())
+    }
+
+    fn part2(pairs: &Self::Parsed) -> color_eyre::Result<Answer> {
+        let total_points = pairs
+            .iter()
+            .map(|&(their_char, outcome_char)| {
+                let desired_result = DesiredResult::try_from(outcome_char)?;
+                Ok(desired_result
+                    .get_round(Move::try_from(their_char)?)
+                    .points())
+            })
+            .sum::<color_eyre::Result<u32>>()?;
+
+        Ok(total_points.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     #[test]
-    fn test_main() {
-        lib_main("A Y\nB X\nC Z")
+    fn test_parts() {
+        let pairs = Day02::parse("A Y\nB X\nC Z").unwrap();
+        assert_eq!(Day02::part1(&pairs).unwrap(), Answer::Number(15));
+        assert_eq!(Day02::part2(&pairs).unwrap(), Answer::Number(12));
     }
 
     #[test]
     fn test_parse_str_to_move_pairs() {
-        let move_pairs = str_to_char_pairs("A X\nB Y\nC Z");
+        let move_pairs = str_to_char_pairs("A X\nB Y\nC Z").unwrap();
         assert_eq!(move_pairs, vec![("A", "X"), ("B", "Y"), ("C", "Z")]);
     }
 
+    #[test]
+    fn test_parse_invalid_pairs() {
+        let err = str_to_char_pairs("A X\nB Q").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.expected, "X, Y or Z");
+
+        let err = str_to_char_pairs("AX").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 3));
+    }
+
     #[test]
     fn test_str_to_move_enum() {
         let current_move = Move::try_from('A').unwrap();
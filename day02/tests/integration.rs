@@ -1,10 +1,13 @@
 #[cfg(test)]
 mod main_tests {
-    use day1_lib::{lib_main, load_input, parse_input_to_moves};
+    use aoc_common::{input::InputSource, Solution};
+    use day2::Day02;
 
     #[test]
     fn main() {
-        let input = load_input();
-        lib_main(input)
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/src/day2.txt");
+        let input = InputSource::from(path).load(2).unwrap();
+        let pairs = Day02::parse(&input).unwrap();
+        println!("{}", Day02::part2(&pairs).unwrap())
     }
 }
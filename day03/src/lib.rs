@@ -1,23 +1,76 @@
-pub fn lib_main(input: &'static str) {
-    let groups = parse_input_to_groups(input);
+use aoc_common::{
+    parse::{parse_lines, ParseError, ParseResult},
+    Answer, Solution,
+};
+use color_eyre::eyre::eyre;
 
-    let mut priorities = Vec::new();
+pub struct Day03;
 
-    for group in groups {
-        let shared_chars = get_shared_chars(group);
+impl Solution for Day03 {
+    const DAY: u8 = 3;
 
-        if shared_chars.len() != 1 {
-            panic!("Expected exactly one shared char");
+    type Parsed = Vec<String>;
+
+    fn parse(input: &str) -> color_eyre::Result<Self::Parsed> {
+        parse_rucksacks(input).map_err(ParseError::into_report)
+    }
+
+    fn part1(rucksacks: &Self::Parsed) -> color_eyre::Result<Answer> {
+        let total = rucksacks
+            .iter()
+            .map(|line| {
+                let rucksack = split_str_to_compartments(line);
+                get_shared_priority(vec![rucksack.compartment_one, rucksack.compartment_two])
+            })
+            .sum::<color_eyre::Result<u32>>()?;
+
+        Ok(total.into())
+    }
+
+    fn part2(rucksacks: &Self::Parsed) -> color_eyre::Result<Answer> {
+        let groups = rucksacks.chunks_exact(3);
+        if !groups.remainder().is_empty() {
+            return Err(color_eyre::eyre::eyre!(
+                "{} rucksacks cannot be split into groups of three",
+                rucksacks.len()
+            ));
         }
 
-        priorities.push(get_char_priority(shared_chars[0]));
+        let total = groups
+            .map(|group| get_shared_priority(group.iter().map(String::as_str).collect()))
+            .sum::<color_eyre::Result<u32>>()?;
+
+        Ok(total.into())
     }
+}
 
-    println!("{}", priorities.iter().sum::<u32>());
+/// Rucksack lines, checked to hold an even number of ASCII letter items
+pub fn parse_rucksacks(input: &str) -> ParseResult<Vec<String>> {
+    parse_lines(input, |line| {
+        if let Some((i, c)) = line.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+            return Err(ParseError::at(
+                line,
+                &line[i..i + c.len_utf8()],
+                "an item letter",
+            ));
+        }
+        if !line.len().is_multiple_of(2) {
+            return Err(ParseError::at(line, line, "an even number of items"));
+        }
+        Ok(line.to_owned())
+    })
 }
 
-pub fn load_input() -> &'static str {
-    include_str!("day3.txt")
+fn get_shared_priority(input_strs: Vec<&str>) -> color_eyre::Result<u32> {
+    let shared_chars = get_shared_chars(input_strs);
+
+    if shared_chars.len() != 1 {
+        return Err(eyre!(
+            "Expected exactly one shared char, found {shared_chars:?}"
+        ));
+    }
+
+    Ok(get_char_priority(shared_chars[0]))
 }
 
 pub fn parse_input_to_groups(input: &str) -> Vec<Vec<&str>> {
@@ -38,6 +91,7 @@ pub fn parse_input_to_groups(input: &str) -> Vec<Vec<&str>> {
 
 pub fn get_shared_chars(input_strs: Vec<&str>) -> Vec<char> {
     // Get intersection of chars in all strings
+    use itertools::Itertools;
     use std::collections::HashSet;
 
     let sets = input_strs
@@ -51,7 +105,8 @@ pub fn get_shared_chars(input_strs: Vec<&str>) -> Vec<char> {
         intersection = intersection.intersection(set).cloned().collect();
     }
 
-    intersection.iter().cloned().collect::<Vec<_>>()
+    // Sort so the output doesn't depend on HashSet iteration order
+    intersection.iter().cloned().sorted().collect::<Vec<_>>()
 }
 
 pub fn get_char_priority(char: char) -> u32 {
@@ -67,14 +122,14 @@ pub fn get_char_priority(char: char) -> u32 {
 }
 
 pub struct Rucksack<'a> {
-    compartment_one: &'a str,
-    compartment_two: &'a str,
+    pub compartment_one: &'a str,
+    pub compartment_two: &'a str,
 }
 
-pub fn split_str_to_compartments(input: &str) -> Rucksack {
+pub fn split_str_to_compartments(input: &str) -> Rucksack<'_> {
     let str_length = input.len();
 
-    if str_length % 2 != 0 {
+    if !str_length.is_multiple_of(2) {
         panic!("Input string must be of even length");
     }
 
@@ -131,13 +186,39 @@ mod tests {
 
     #[test]
     fn test_load_input() {
-        let input = load_input();
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/src/day3.txt");
+        let input = aoc_common::input::InputSource::from(path).load(3).unwrap();
         assert_eq!(
             input.lines().take(1).collect::<Vec<_>>()[0],
             "FqdWDFppHWhmwwzdjvjTRTznjdMv"
         );
     }
 
+    #[test]
+    fn test_parts() {
+        let input = "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw";
+        let rucksacks = Day03::parse(input).unwrap();
+        assert_eq!(Day03::part1(&rucksacks).unwrap(), Answer::Number(157));
+        assert_eq!(Day03::part2(&rucksacks).unwrap(), Answer::Number(70));
+
+        let incomplete = Day03::parse("vJrwpWtwJgWrhcsFMMfFFhFp").unwrap();
+        assert!(Day03::part2(&incomplete).is_err());
+    }
+
+    #[test]
+    fn test_parse_invalid_rucksacks() {
+        let err = parse_rucksacks("AAbb\nAB-c").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+
+        let err = parse_rucksacks("AAb").unwrap_err();
+        assert_eq!(err.expected, "an even number of items");
+    }
+
     #[test]
     fn test_parse_input_into_groups() {
         let groups = parse_input_to_groups("1\n2\n3\n4");
@@ -1,10 +1,13 @@
 #[cfg(test)]
 mod main_tests {
-    use day1_lib::{lib_main, load_input};
+    use aoc_common::{input::InputSource, Solution};
+    use day3::Day03;
 
     #[test]
     fn main() {
-        let input = load_input();
-        lib_main(input)
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/src/day3.txt");
+        let input = InputSource::from(path).load(3).unwrap();
+        let rucksacks = Day03::parse(&input).unwrap();
+        println!("{}", Day03::part2(&rucksacks).unwrap())
     }
 }
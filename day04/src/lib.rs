@@ -1,5 +1,8 @@
-use color_eyre::eyre::{eyre, Result};
-use itertools::Itertools;
+use aoc_common::{
+    parse::{parse_lines, ParseError, ParseResult},
+    Answer, Solution,
+};
+use color_eyre::eyre::Result;
 #[derive(PartialEq, Debug, Clone)]
 pub struct ZoneAssignment {
     range: std::ops::RangeInclusive<u32>,
@@ -8,55 +11,89 @@ impl ZoneAssignment {
     fn has_overlap(&self, other: Self) -> bool {
         !(self.range.start() > other.range.end() || self.range.end() < other.range.start())
     }
+
+    fn fully_contains(&self, other: &Self) -> bool {
+        self.range.start() <= other.range.start() && self.range.end() >= other.range.end()
+    }
 }
 
 impl TryFrom<&str> for ZoneAssignment {
-    type Error = color_eyre::Report;
+    type Error = ParseError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        // Parse string into values of u32
-        let values = value
-            .split('-')
-            .map(|x| x.parse::<u32>())
-            .map(|x| x.unwrap())
-            .collect::<Vec<u32>>();
-
-        let (min, max) = match (values.iter().min(), values.iter().max()) {
-            (Some(min), Some(max)) => (*min, *max),
-            _ => return Err(eyre!("Invalid input")),
+        let expected = "a section range like 2-4";
+        let bounds = value.split_once('-').filter(|(_, end)| !end.contains('-'));
+        let Some((start, end)) = bounds else {
+            return Err(ParseError::at(value, value, expected));
         };
 
-        Ok(ZoneAssignment { range: min..=max })
+        let number = |x: &str| {
+            x.parse::<u32>()
+                .map_err(|_| ParseError::at(value, x, "a section number"))
+        };
+        let (start, end) = (number(start)?, number(end)?);
+        if start > end {
+            return Err(ParseError::at(value, value, expected));
+        }
+
+        Ok(ZoneAssignment { range: start..=end })
     }
 }
 
-pub fn parse_elf_pairs(input: &str) -> Vec<ZoneAssignment> {
+pub fn parse_elf_pairs(input: &str) -> ParseResult<Vec<ZoneAssignment>> {
     input
         .split(',')
-        .map(|x| ZoneAssignment::try_from(x).unwrap())
-        .collect::<Vec<ZoneAssignment>>()
+        .map(|x| ZoneAssignment::try_from(x).map_err(|err| err.within(input, x)))
+        .collect::<ParseResult<Vec<ZoneAssignment>>>()
 }
 
-fn main() {
-    println!("Testing!");
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+
+    type Parsed = Vec<(ZoneAssignment, ZoneAssignment)>;
+
+    fn parse(input: &str) -> Result<Self::Parsed> {
+        parse_lines(input, |line| match parse_elf_pairs(line)?.as_slice() {
+            [first, second] => Ok((first.clone(), second.clone())),
+            _ => Err(ParseError::at(
+                line,
+                line,
+                "two comma-separated assignments",
+            )),
+        })
+        .map_err(ParseError::into_report)
+    }
+
+    fn part1(pairs: &Self::Parsed) -> Result<Answer> {
+        let fully_contained = pairs
+            .iter()
+            .filter(|(first, second)| first.fully_contains(second) || second.fully_contains(first))
+            .count();
+
+        Ok(fully_contained.into())
+    }
+
+    fn part2(pairs: &Self::Parsed) -> Result<Answer> {
+        let has_overlap = pairs
+            .iter()
+            .filter(|(first, second)| first.has_overlap(second.clone()))
+            .count();
+
+        Ok(has_overlap.into())
+    }
 }
 
+#[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn run_main() {
         let input = include_str!("input.txt");
-        let elf_pairs = input.lines().map(parse_elf_pairs).collect::<Vec<_>>();
-
-        let mut has_overlap = 0;
-        for pair in elf_pairs.iter() {
-            let first = &pair[0];
-            let second = &pair[1];
-            if first.has_overlap(second.clone()) {
-                has_overlap += 1;
-            }
-        }
+        let pairs = Day04::parse(input).unwrap();
+        let has_overlap = Day04::part2(&pairs).unwrap();
 
         dbg!(has_overlap);
     }
@@ -64,7 +101,7 @@ mod tests {
     #[test]
     fn test_parse_elf_pair() {
         let input = "1-3,5-7";
-        let result = parse_elf_pairs(input);
+        let result = parse_elf_pairs(input).unwrap();
 
         assert_eq!(
             result,
@@ -85,10 +122,34 @@ mod tests {
         assert!(!assignment.has_overlap(other_no_overlap));
     }
 
+    #[test]
+    fn test_fully_contains() {
+        let assignment = ZoneAssignment { range: 2..=8 };
+        assert!(assignment.fully_contains(&ZoneAssignment { range: 3..=7 }));
+        assert!(!assignment.fully_contains(&ZoneAssignment { range: 3..=9 }));
+    }
+
     #[test]
     fn test_assignment_parsing() {
         let assignment = ZoneAssignment::try_from("1-3").unwrap();
         assert_eq!(assignment.range.start(), &1);
         assert_eq!(assignment.range.end(), &3);
     }
+
+    #[test]
+    fn test_invalid_assignment() {
+        for assignment in ["3-1", "1-2-3", "5"] {
+            let err = ZoneAssignment::try_from(assignment).unwrap_err();
+            assert_eq!(err.snippet, assignment);
+            assert_eq!(err.expected, "a section range like 2-4");
+        }
+    }
+
+    #[test]
+    fn test_invalid_pair() {
+        let err = Day04::parse("1-3,5-7\n1-3,5-x").unwrap_err();
+        let err = err.downcast::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column), (2, 7));
+        assert_eq!(err.snippet, "x");
+    }
 }
@@ -1,92 +1,243 @@
-use std::str::Lines;
+use std::{fmt, str::Lines};
+
+use aoc_common::{
+    parse::{ParseError, ParseResult},
+    Answer, Solution,
+};
+use color_eyre::{Section, SectionExt};
+use itertools::Itertools;
 
 pub fn add(left: usize, right: usize) -> usize {
     left + right
 }
 
+/// The crane model used to rearrange the stacks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrateMover {
+    /// Moves crates one at a time, reversing their order
+    Model9000,
+    /// Moves several crates at once, keeping their order
+    Model9001,
+}
+
 #[derive(Debug, PartialEq, Clone)]
-struct Stacks {
+pub struct Stacks {
     stacks: Vec<Vec<char>>,
 }
 
+/// Renders the stacks in the same `[X]` column format as the puzzle input
+impl fmt::Display for Stacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let height = self.stacks.iter().map(Vec::len).max().unwrap_or(0);
+
+        for row in (0..height).rev() {
+            let cells = self.stacks.iter().map(|stack| match stack.get(row) {
+                Some(element) => format!("[{element}]"),
+                None => "   ".to_string(),
+            });
+            writeln!(f, "{}", cells.format(" "))?;
+        }
+
+        let labels = (1..=self.stacks.len()).map(|label| format!("{label:^3}"));
+        write!(f, "{}", labels.format(" "))
+    }
+}
+
 impl TryFrom<Lines<'_>> for Stacks {
-    type Error = color_eyre::Report;
+    type Error = ParseError;
 
     fn try_from(lines: Lines) -> Result<Self, Self::Error> {
-        // Find first row where all chars are spaces or numbers
+        let lines = lines.collect::<Vec<_>>();
+
+        // Find first row made only of stack labels
         let index_row = lines
-            .clone()
-            .enumerate()
-            .find(|(_, line)| {
-                line.chars()
-                    .all(|c| c.is_ascii_digit() || c.is_ascii_whitespace())
+            .iter()
+            .position(|line| {
+                line.chars().any(|c| c.is_ascii_digit())
+                    && line
+                        .chars()
+                        .all(|c| c.is_ascii_digit() || c.is_ascii_whitespace())
             })
-            .map(|(i, _)| i)
-            .ok_or_else(|| color_eyre::eyre::eyre!("No index row found"))?;
+            .ok_or_else(|| {
+                let last_line = lines.last().copied().unwrap_or_default();
+                ParseError::at(last_line, "", "a row of stack numbers")
+                    .with_line(lines.len().max(1))
+            })?;
+
+        let stack_count =
+            parse_stack_labels(lines[index_row]).map_err(|err| err.with_line(index_row + 1))?;
+
+        let mut stacks = vec![vec![]; stack_count];
+        for (line_number, line) in lines[..index_row].iter().enumerate().rev() {
+            let row =
+                parse_crate_row(line, stack_count).map_err(|err| err.with_line(line_number + 1))?;
+            for (stack, element) in stacks.iter_mut().zip(row) {
+                stack.extend(element);
+            }
+        }
 
-        // Find the stack positions
-        let stack_positions = lines
-            .clone()
-            .nth(index_row)
-            .ok_or_else(|| color_eyre::eyre::eyre!("No index row found"))?
-            .chars()
-            .enumerate()
-            .filter(|(_, c)| *c != ' ')
-            .map(|(i, _)| i)
-            .collect::<Vec<_>>();
+        Ok(Self { stacks })
+    }
+}
 
-        let mut stacks = vec![];
-        for _ in stack_positions.iter() {
-            stacks.push(vec![]);
+/// Checks that the index row numbers the stacks 1, 2, 3, ... and returns how many there are
+fn parse_stack_labels(line: &str) -> ParseResult<usize> {
+    let labels = line.split_ascii_whitespace().collect::<Vec<_>>();
+
+    for (expected, label) in (1..).zip(&labels) {
+        if label.parse::<usize>() != Ok(expected) {
+            return Err(ParseError::at(
+                line,
+                label,
+                format!("stack label `{expected}`"),
+            ));
         }
+    }
 
-        for line in lines.into_iter() {
-            for (i, position) in stack_positions.iter().enumerate() {
-                let element = line.chars().nth(*position).unwrap();
-                if element != ' ' && !element.is_ascii_digit() {
-                    stacks[i].push(element);
-                }
-            }
+    Ok(labels.len())
+}
+
+/// Parses one row of `[X]` cells separated by single spaces. Rows may be
+/// shorter than the index row, e.g. when trailing whitespace was trimmed.
+fn parse_crate_row(line: &str, stack_count: usize) -> ParseResult<Vec<Option<char>>> {
+    let mut row = vec![None; stack_count];
+    let mut rest = line;
+
+    for (i, element) in row.iter_mut().enumerate() {
+        if i > 0 && !rest.is_empty() {
+            rest = rest.strip_prefix(' ').ok_or_else(|| {
+                let found = rest.chars().next().map_or("", |c| &rest[..c.len_utf8()]);
+                ParseError::at(line, found, "a space between crates")
+            })?;
         }
 
-        stacks.iter_mut().for_each(|stack| stack.reverse());
+        let cell_len = rest.char_indices().nth(3).map_or(rest.len(), |(j, _)| j);
+        let (cell, after) = rest.split_at(cell_len);
+        *element = parse_crate_cell(cell)
+            .ok_or_else(|| ParseError::at(line, cell, "a crate like `[A]` or an empty slot"))?;
+        rest = after;
+    }
 
-        Ok(Self { stacks })
+    let extra = rest.trim();
+    if !extra.is_empty() {
+        return Err(ParseError::at(
+            line,
+            extra,
+            format!("at most {stack_count} stacks"),
+        ));
+    }
+
+    Ok(row)
+}
+
+/// `Some(None)` for an empty slot, `Some(Some(x))` for `[x]`, `None` if malformed
+fn parse_crate_cell(cell: &str) -> Option<Option<char>> {
+    if cell.trim().is_empty() {
+        return Some(None);
+    }
+
+    match cell.chars().collect::<Vec<_>>()[..] {
+        ['[', element, ']'] if !element.is_whitespace() && !"[]".contains(element) => {
+            Some(Some(element))
+        }
+        _ => None,
     }
 }
 
 impl Stacks {
-    fn apply_instructions(
-        self,
+    pub fn apply_instructions(
+        mut self,
         instructions: Vec<Instruction>,
+        model: CrateMover,
     ) -> Result<Self, color_eyre::Report> {
-        let starting_state = self.clone();
+        for (index, instruction) in instructions.iter().enumerate() {
+            self.apply_instruction(instruction, model)
+                .map_err(|err| self.failure_report(err, index, instruction))?;
+        }
 
-        instructions
-            .into_iter()
-            .try_fold(starting_state, |stacks, instruction| {
-                stacks.apply_instruction(instruction)
-            })
+        Ok(self)
     }
 
-    fn apply_instruction(mut self, instruction: Instruction) -> Result<Self, color_eyre::Report> {
-        // Get slice of size n from top of stack
-        let stack_len = self.stacks[instruction.from - 1].len();
+    /// Like [`Stacks::apply_instructions`], but yields the starting state and
+    /// the state after every instruction. The trace ends at the first invalid
+    /// instruction.
+    pub fn trace(self, instructions: Vec<Instruction>, model: CrateMover) -> Trace {
+        Trace {
+            stacks: Some(self),
+            instructions: instructions.into_iter().enumerate(),
+            model,
+            started: false,
+        }
+    }
 
-        let elements_to_move = self
-            .stacks
-            .get_mut(instruction.from - 1)
-            .ok_or_else(|| color_eyre::eyre::eyre!("No stack found"))?
+    /// Applies a single instruction, leaving the stacks untouched if it is invalid
+    fn apply_instruction(
+        &mut self,
+        instruction: &Instruction,
+        model: CrateMover,
+    ) -> Result<(), InvalidMove> {
+        let (from, to) = self.validate(instruction)?;
+
+        // Take n elements from the top of the from stack
+        let stack_len = self.stacks[from].len();
+        let mut elements_to_move = self.stacks[from]
             .drain(stack_len - instruction.n..)
             .collect::<Vec<_>>();
 
+        // The 9000 lifts one crate at a time, so the top crate lands first
+        if model == CrateMover::Model9000 {
+            elements_to_move.reverse();
+        }
+
         // Push those elements to the top of the to stack
-        self.stacks
-            .get_mut(instruction.to - 1)
-            .ok_or_else(|| color_eyre::eyre::eyre!("No stack found"))?
-            .extend(elements_to_move);
+        self.stacks[to].extend(elements_to_move);
+
+        Ok(())
+    }
+
+    /// Checks an instruction against the current stacks, returning the
+    /// zero-based indices of the from and to stacks
+    fn validate(&self, instruction: &Instruction) -> Result<(usize, usize), InvalidMove> {
+        let index = |label: usize| match label {
+            0 => Err(InvalidMove::ZeroIndex),
+            label if label > self.stacks.len() => Err(InvalidMove::UnknownStack {
+                label,
+                count: self.stacks.len(),
+            }),
+            label => Ok(label - 1),
+        };
+
+        let (from, to) = (index(instruction.from)?, index(instruction.to)?);
+
+        if from == to {
+            return Err(InvalidMove::SameStack(instruction.from));
+        }
+
+        let available = self.stacks[from].len();
+        if available < instruction.n {
+            return Err(InvalidMove::NotEnoughCrates {
+                stack: instruction.from,
+                available,
+                requested: instruction.n,
+            });
+        }
 
-        Ok(self.clone())
+        Ok((from, to))
+    }
+
+    /// Explains which instruction failed and shows the stacks it was applied to
+    fn failure_report(
+        &self,
+        err: InvalidMove,
+        index: usize,
+        instruction: &Instruction,
+    ) -> color_eyre::Report {
+        color_eyre::Report::new(err)
+            .wrap_err(format!(
+                "instruction {} (`{instruction}`) failed",
+                index + 1
+            ))
+            .section(self.to_string().header("Stacks:"))
     }
 
     fn get_top_elements(&self) -> Vec<char> {
@@ -97,29 +248,181 @@ impl Stacks {
     }
 }
 
-#[derive(Debug, PartialEq)]
-struct Instruction {
+/// Iterator over the intermediate states of a simulation, see [`Stacks::trace`]
+pub struct Trace {
+    stacks: Option<Stacks>,
+    instructions: std::iter::Enumerate<std::vec::IntoIter<Instruction>>,
+    model: CrateMover,
+    started: bool,
+}
+
+impl Iterator for Trace {
+    type Item = Result<Stacks, color_eyre::Report>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let stacks = self.stacks.as_mut()?;
+
+        if !self.started {
+            self.started = true;
+            return Some(Ok(stacks.clone()));
+        }
+
+        let (index, instruction) = self.instructions.next()?;
+        match stacks.apply_instruction(&instruction, self.model) {
+            Ok(()) => Some(Ok(stacks.clone())),
+            Err(err) => {
+                let report = stacks.failure_report(err, index, &instruction);
+                self.stacks = None;
+                Some(Err(report))
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Instruction {
     n: usize,
     from: usize,
     to: usize,
 }
 
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.n, self.from, self.to)
+    }
+}
+
+/// Why an instruction cannot be applied to the current stacks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvalidMove {
+    /// Stacks are numbered from 1
+    ZeroIndex,
+    UnknownStack {
+        label: usize,
+        count: usize,
+    },
+    SameStack(usize),
+    NotEnoughCrates {
+        stack: usize,
+        available: usize,
+        requested: usize,
+    },
+}
+
+impl fmt::Display for InvalidMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroIndex => write!(f, "stacks are numbered from 1"),
+            Self::UnknownStack { label, count } => {
+                write!(f, "stack {label} does not exist (there are {count})")
+            }
+            Self::SameStack(label) => {
+                write!(f, "cannot move crates from stack {label} onto itself")
+            }
+            Self::NotEnoughCrates {
+                stack,
+                available,
+                requested,
+            } => write!(
+                f,
+                "cannot move {requested} crates from stack {stack}, which holds {available}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InvalidMove {}
+
 impl TryFrom<&str> for Instruction {
-    type Error = color_eyre::Report;
+    type Error = ParseError;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
         let parts = value.split_ascii_whitespace().collect::<Vec<_>>();
 
+        let [_, n, _, from, _, to] = parts.as_slice() else {
+            return Err(ParseError::at(value, value, "`move N from A to B`"));
+        };
+
+        for (part, keyword) in parts.iter().step_by(2).zip(["move", "from", "to"]) {
+            if *part != keyword {
+                return Err(ParseError::at(value, part, format!("`{keyword}`")));
+            }
+        }
+
+        let number = |part: &str| {
+            part.parse()
+                .map_err(|_| ParseError::at(value, part, "a number"))
+        };
+
         let instructions = Self {
-            n: parts[1].parse()?,
-            from: parts[3].parse()?,
-            to: parts[5].parse()?,
+            n: number(n)?,
+            from: number(from)?,
+            to: number(to)?,
         };
 
         Ok(instructions)
     }
 }
 
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+
+    type Parsed = (Stacks, Vec<Instruction>);
+
+    fn parse(input: &str) -> color_eyre::Result<Self::Parsed> {
+        parse_input(input).map_err(ParseError::into_report)
+    }
+
+    fn part1((starting_state, instructions): &Self::Parsed) -> color_eyre::Result<Answer> {
+        top_elements_after(starting_state, instructions, CrateMover::Model9000)
+    }
+
+    fn part2((starting_state, instructions): &Self::Parsed) -> color_eyre::Result<Answer> {
+        top_elements_after(starting_state, instructions, CrateMover::Model9001)
+    }
+}
+
+fn top_elements_after(
+    starting_state: &Stacks,
+    instructions: &[Instruction],
+    model: CrateMover,
+) -> color_eyre::Result<Answer> {
+    let result = starting_state
+        .clone()
+        .apply_instructions(instructions.to_vec(), model)?;
+    Ok(result
+        .get_top_elements()
+        .into_iter()
+        .collect::<String>()
+        .into())
+}
+
+fn parse_input(starting_input: &str) -> ParseResult<(Stacks, Vec<Instruction>)> {
+    let lines = starting_input.lines().collect::<Vec<_>>();
+
+    // The starting state and the instructions are separated by the first empty line
+    let separator = lines
+        .iter()
+        .position(|line| line.is_empty())
+        .unwrap_or(lines.len());
+
+    // Parse the starting state
+    let starting_state = Stacks::try_from(lines[..separator].join("\n").lines())?;
+
+    // Every non-empty line after the separator must be an instruction
+    let instructions = lines
+        .iter()
+        .enumerate()
+        .skip(separator + 1)
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| Instruction::try_from(*line).map_err(|err| err.with_line(i + 1)))
+        .collect::<ParseResult<Vec<_>>>()?;
+
+    Ok((starting_state, instructions))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,14 +439,110 @@ mod tests {
             to: 3,
         }];
 
-        let result = stacks.apply_instructions(instructions).unwrap();
+        let result = stacks
+            .clone()
+            .apply_instructions(instructions.clone(), CrateMover::Model9001)
+            .unwrap();
         let expected = Stacks {
             stacks: vec![vec![], vec!['C'], vec!['P', 'P', 'C', 'M']],
         };
+        assert_eq!(result, expected);
 
+        let result = stacks
+            .apply_instructions(instructions, CrateMover::Model9000)
+            .unwrap();
+        let expected = Stacks {
+            stacks: vec![vec![], vec!['C'], vec!['P', 'P', 'M', 'C']],
+        };
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_invalid_moves() {
+        let stacks = Stacks {
+            stacks: vec![vec!['Z', 'N'], vec!['M'], vec![]],
+        };
+        let check = |n, from, to| stacks.validate(&Instruction { n, from, to }).unwrap_err();
+
+        assert_eq!(check(1, 0, 2), InvalidMove::ZeroIndex);
+        assert_eq!(
+            check(1, 1, 4),
+            InvalidMove::UnknownStack { label: 4, count: 3 }
+        );
+        assert_eq!(check(1, 2, 2), InvalidMove::SameStack(2));
+        assert_eq!(
+            check(3, 1, 3),
+            InvalidMove::NotEnoughCrates {
+                stack: 1,
+                available: 2,
+                requested: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_failed_instruction_report() {
+        let stacks = Stacks {
+            stacks: vec![vec!['Z', 'N'], vec!['M']],
+        };
+        let instructions = vec![
+            Instruction {
+                n: 1,
+                from: 1,
+                to: 2,
+            },
+            Instruction {
+                n: 3,
+                from: 2,
+                to: 1,
+            },
+        ];
+
+        let err = stacks
+            .apply_instructions(instructions, CrateMover::Model9000)
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "instruction 2 (`move 3 from 2 to 1`) failed"
+        );
+        assert!(matches!(
+            err.downcast_ref::<InvalidMove>(),
+            Some(InvalidMove::NotEnoughCrates { available: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let (stacks, _) = parse_input(include_str!("starting_input.txt")).unwrap();
+        let rendered = stacks.to_string();
+
+        assert_eq!(
+            rendered,
+            "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 "
+        );
+        assert_eq!(Stacks::try_from(rendered.lines()).unwrap(), stacks);
+    }
+
+    #[test]
+    fn test_trace() {
+        let (stacks, instructions) = parse_input(include_str!("starting_input.txt")).unwrap();
+        let states = stacks
+            .trace(instructions, CrateMover::Model9000)
+            .map(|state| state.unwrap().to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(states.len(), 5);
+        assert_eq!(
+            states[1],
+            "[D]        \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 "
+        );
+        assert_eq!(
+            states[4],
+            "        [Z]\n        [N]\n        [D]\n[C] [M] [P]\n 1   2   3 "
+        );
+    }
+
     #[test]
     fn test_starting_state_parsing() {
         let starting_state_input = "[C]     [P]\n[N] [C] [P]\n 1   2   3  ".lines();
@@ -154,6 +553,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_ragged_starting_state() {
+        let trimmed = "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3";
+        let stacks = Stacks::try_from(trimmed.lines()).unwrap();
+        assert_eq!(
+            stacks.stacks,
+            vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']]
+        );
+    }
+
+    #[test]
+    fn test_two_digit_labels() {
+        let stacks = Stacks {
+            stacks: (b'A'..=b'K').map(|c| vec![c as char]).collect(),
+        };
+        let rendered = stacks.to_string();
+
+        assert!(rendered.ends_with(" 9  10  11 "));
+        assert_eq!(Stacks::try_from(rendered.lines()).unwrap(), stacks);
+    }
+
+    #[test]
+    fn test_malformed_starting_state() {
+        let err = Stacks::try_from("[N] [C\n 1   2".lines()).unwrap_err();
+        assert_eq!((err.line, err.column, err.snippet.as_str()), (1, 5, "[C"));
+
+        let err = Stacks::try_from("[N]-[C]\n 1   2".lines()).unwrap_err();
+        assert_eq!(
+            (err.column, err.expected.as_str()),
+            (4, "a space between crates")
+        );
+
+        let err = Stacks::try_from("[N] [C] [P]\n 1   2".lines()).unwrap_err();
+        assert_eq!((err.column, err.snippet.as_str()), (9, "[P]"));
+
+        let err = Stacks::try_from("[N] [C]\n 1   3".lines()).unwrap_err();
+        assert_eq!((err.line, err.expected.as_str()), (2, "stack label `2`"));
+    }
+
     #[test]
     fn test_instruction_parsing() {
         let instructions = "move 1 from 2 to 3\nmove 2 from 2 to 3\nmove 3 from 2 to 4\n"
@@ -183,50 +621,46 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_empty_input() {
+        let err = parse_input("").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 1));
+        assert_eq!(err.excerpt(), "1 | \n  | ^");
+    }
+
+    #[test]
+    fn test_invalid_instruction() {
+        let err = Instruction::try_from("move 1 from x to 3").unwrap_err();
+        assert_eq!((err.column, err.snippet.as_str()), (13, "x"));
+
+        let err = Instruction::try_from("move 1 to 3 from 2").unwrap_err();
+        assert_eq!(err.expected, "`from`");
+
+        let input = include_str!("starting_input.txt").replace("move 2", "move two");
+        let err = parse_input(&input).unwrap_err();
+        assert_eq!((err.line, err.column), (8, 6));
+
+        // Misspelled instructions must not be skipped
+        let input = include_str!("starting_input.txt").replace("move 1 from 2", "mvoe 1 from 2");
+        let err = parse_input(&input).unwrap_err();
+        assert_eq!((err.line, err.snippet.as_str()), (6, "mvoe"));
+    }
+
     #[test]
     fn starting_example() {
         let starting_input = include_str!("starting_input.txt");
 
-        let result = main(starting_input);
+        let parsed = Day05::parse(starting_input).unwrap();
 
-        let top_elements_end_state = vec!['M', 'C', 'D'];
-        assert_eq!(result, top_elements_end_state);
+        assert_eq!(Day05::part1(&parsed).unwrap(), Answer::from("CMZ"));
+        assert_eq!(Day05::part2(&parsed).unwrap(), Answer::from("MCD"));
     }
 
     #[test]
     fn final_result() {
         let input = include_str!("input.txt");
-        let result = main(input);
+        let parsed = Day05::parse(input).unwrap();
+        let result = Day05::part2(&parsed).unwrap();
         dbg!(result);
     }
 }
-
-fn main(starting_input: &str) -> Vec<char> {
-    // Collect lines untill the first empty line
-    let binding = starting_input
-        .lines()
-        .take_while(|line| !line.is_empty())
-        .collect::<Vec<_>>()
-        .join("\n");
-
-    let starting_state_lines = binding.lines();
-
-    // Parse the starting state
-    let starting_state = Stacks::try_from(starting_state_lines).unwrap();
-
-    // Find all lines that start with "move"
-    let instructions_lines = starting_input
-        .lines()
-        .skip_while(|line| !line.starts_with("move"))
-        .collect::<Vec<_>>();
-
-    // Parse the instructions
-    let instructions = instructions_lines
-        .iter()
-        .map(|line| Instruction::try_from(line.clone()).unwrap())
-        .collect::<Vec<_>>();
-
-    // Create a new set of stacks
-    let result = starting_state.apply_instructions(instructions).unwrap();
-    result.get_top_elements()
-}
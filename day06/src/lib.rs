@@ -1,17 +1,26 @@
+use aoc_common::{
+    parse::{ParseError, ParseResult},
+    Answer, Solution,
+};
+use color_eyre::eyre::eyre;
 use itertools::Itertools;
-use std::collections::HashSet;
 
 pub fn add(left: usize, right: usize) -> usize {
     left + right
 }
 
-struct TestInput<'a> {
-    input: &'a str,
-    expected_index: usize,
-}
+fn parse_datastream(input: &str) -> ParseResult<String> {
+    let datastream = input.trim();
+
+    if let Some((i, c)) = datastream
+        .char_indices()
+        .find(|(_, c)| !c.is_ascii_lowercase())
+    {
+        let snippet = &datastream[i..i + c.len_utf8()];
+        return Err(ParseError::at(datastream, snippet, "a lowercase letter").with_line(1));
+    }
 
-fn get_position_of_input(input: &TestInput, window_size: u32) -> Option<usize> {
-    get_end_index_of_first_unique_chunk(input.input, window_size).map(|pos| pos)
+    Ok(datastream.to_owned())
 }
 
 fn get_end_index_of_first_unique_chunk(input: &str, window_size: u32) -> Option<usize> {
@@ -23,16 +32,43 @@ fn get_end_index_of_first_unique_chunk(input: &str, window_size: u32) -> Option<
         .map(|pos| pos + window_usize)
 }
 
-fn get_char_at_index(input: &str, index: usize) -> Option<char> {
-    input.chars().nth(index)
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u8 = 6;
+
+    type Parsed = String;
+
+    fn parse(input: &str) -> color_eyre::Result<Self::Parsed> {
+        parse_datastream(input).map_err(ParseError::into_report)
+    }
+
+    fn part1(input: &Self::Parsed) -> color_eyre::Result<Answer> {
+        get_end_index_of_first_unique_chunk(input, 4)
+            .map(Answer::from)
+            .ok_or_else(|| eyre!("No start-of-packet marker found"))
+    }
+
+    fn part2(input: &Self::Parsed) -> color_eyre::Result<Answer> {
+        get_end_index_of_first_unique_chunk(input, 14)
+            .map(Answer::from)
+            .ok_or_else(|| eyre!("No start-of-message marker found"))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::usize;
-
     use super::*;
 
+    struct TestInput<'a> {
+        input: &'a str,
+        expected_index: usize,
+    }
+
+    fn get_position_of_input(input: &TestInput, window_size: u32) -> Option<usize> {
+        get_end_index_of_first_unique_chunk(input.input, window_size)
+    }
+
     #[test]
     fn main() {
         let input = include_str!("input.txt");
@@ -42,6 +78,11 @@ mod tests {
     }
 
     #[test]
+    fn test_invalid_datastream() {
+        let err = parse_datastream("abc1d\n").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 4));
+    }
+
     #[test]
     fn check_inputs() {
         let inputs = vec![
@@ -64,10 +105,7 @@ mod tests {
         ];
 
         for input in &inputs {
-            assert_eq!(
-                get_position_of_input(&input, 14),
-                Some(input.expected_index)
-            );
+            assert_eq!(get_position_of_input(input, 14), Some(input.expected_index));
         }
     }
 }
@@ -0,0 +1,176 @@
+//! Round-tripping between a reconstructed [`Filesystem`] and a real directory.
+
+use std::fs;
+
+use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
+use color_eyre::eyre::{eyre, WrapErr};
+
+use crate::{Filesystem, NodeKind};
+
+/// Recreates every directory and file under `root`. Files are created sparse,
+/// so their listed sizes take no real disk space.
+pub fn materialize(filesystem: &Filesystem, root: &Utf8Path) -> color_eyre::Result<()> {
+    let tree = filesystem.tree();
+    let Some(root_id) = tree.root_node_id() else {
+        return Ok(());
+    };
+
+    for node in tree.traverse_pre_order(root_id)? {
+        let node = node.data();
+        let target = on_disk(root, node.path())?;
+        match node.kind() {
+            NodeKind::Dir => fs::create_dir_all(&target),
+            NodeKind::File => fs::File::create(&target).and_then(|file| file.set_len(node.size())),
+        }
+        .wrap_err_with(|| format!("Failed to create `{target}`"))?;
+    }
+
+    Ok(())
+}
+
+/// The location of an absolute tree path below `root`. Only plain names are
+/// allowed after the leading `/`, so the result can never leave `root`.
+pub fn on_disk(root: &Utf8Path, path: &Utf8Path) -> color_eyre::Result<Utf8PathBuf> {
+    let mut target = root.to_path_buf();
+    for (i, component) in path.components().enumerate() {
+        match component {
+            Utf8Component::RootDir if i == 0 => {}
+            Utf8Component::Normal(name) => target.push(name),
+            _ => return Err(eyre!("`{path}` does not stay below the root")),
+        }
+    }
+    Ok(target)
+}
+
+/// Writes a `$ cd` / `$ ls` transcript that explores `root` depth first,
+/// listing entries in name order
+pub fn transcript_from_dir(root: &Utf8Path) -> color_eyre::Result<String> {
+    let mut lines = vec!["$ cd /".to_string()];
+    explore(root, &mut lines)?;
+    Ok(lines.join("\n"))
+}
+
+fn explore(dir: &Utf8Path, lines: &mut Vec<String>) -> color_eyre::Result<()> {
+    let mut entries = dir
+        .read_dir_utf8()
+        .wrap_err_with(|| format!("Failed to read `{dir}`"))?
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by(|a, b| a.file_name().cmp(b.file_name()));
+
+    lines.push("$ ls".to_string());
+    let mut subdirs = vec![];
+    for entry in &entries {
+        let metadata = entry.metadata()?;
+        if metadata.is_dir() {
+            lines.push(format!("dir {}", entry.file_name()));
+            subdirs.push(entry.path());
+        } else if metadata.is_file() {
+            lines.push(format!("{} {}", metadata.len(), entry.file_name()));
+        } else {
+            return Err(eyre!(
+                "`{}` is neither a file nor a directory",
+                entry.path()
+            ));
+        }
+    }
+
+    for subdir in subdirs {
+        lines.push(format!("$ cd {}", subdir.file_name().unwrap_or_default()));
+        explore(subdir, lines)?;
+        lines.push("$ cd ..".to_string());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn temp_root() -> (tempfile::TempDir, Utf8PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = Utf8PathBuf::try_from(dir.path().to_path_buf()).unwrap();
+        (dir, root)
+    }
+
+    #[test]
+    fn test_sizes_match_metadata() {
+        let filesystem = Filesystem::from_transcript(include_str!("starting_input.txt")).unwrap();
+        let (_dir, root) = temp_root();
+        materialize(&filesystem, &root).unwrap();
+
+        for path in ["/b.txt", "/c.dat", "/a/f", "/a/h.lst"] {
+            let metadata = fs::metadata(on_disk(&root, path.into()).unwrap()).unwrap();
+            assert_eq!(Some(metadata.len()), filesystem.size_of(path));
+        }
+        assert!(on_disk(&root, "/d".into()).unwrap().is_dir());
+    }
+
+    #[test]
+    fn test_parent_entry_cannot_leave_root() {
+        let (_dir, root) = temp_root();
+        for path in ["/../escaped.txt", "/a/../../escaped.txt", "/a/.."] {
+            assert!(on_disk(&root, path.into()).is_err(), "{path}");
+        }
+        assert_eq!(on_disk(&root, "/a/b".into()).unwrap(), root.join("a/b"));
+    }
+
+    #[derive(Debug, Clone)]
+    enum Entry {
+        File(u64),
+        Dir(BTreeMap<String, Entry>),
+    }
+
+    fn entries() -> impl Strategy<Value = BTreeMap<String, Entry>> {
+        let leaf = (0..1_000_000u64).prop_map(Entry::File);
+        let entry = leaf.prop_recursive(3, 24, 4, |inner| {
+            prop::collection::btree_map("[a-z][a-z0-9._-]{0,7}", inner, 0..4).prop_map(Entry::Dir)
+        });
+        prop::collection::btree_map("[a-z][a-z0-9._-]{0,7}", entry, 0..5)
+    }
+
+    fn write_transcript(dir: &BTreeMap<String, Entry>, lines: &mut Vec<String>) {
+        lines.push("$ ls".to_string());
+        for (name, entry) in dir {
+            match entry {
+                Entry::File(size) => lines.push(format!("{size} {name}")),
+                Entry::Dir(_) => lines.push(format!("dir {name}")),
+            }
+        }
+        for (name, entry) in dir {
+            if let Entry::Dir(children) = entry {
+                lines.push(format!("$ cd {name}"));
+                write_transcript(children, lines);
+                lines.push("$ cd ..".to_string());
+            }
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn transcript_survives_disk_round_trip(root_entries in entries()) {
+            let mut lines = vec!["$ cd /".to_string()];
+            write_transcript(&root_entries, &mut lines);
+            let transcript = lines.join("\n");
+
+            let filesystem = Filesystem::from_transcript(&transcript).unwrap();
+            let (_dir, root) = temp_root();
+            materialize(&filesystem, &root).unwrap();
+
+            let regenerated = transcript_from_dir(&root).unwrap();
+            prop_assert_eq!(&regenerated, &transcript);
+
+            let reparsed = Filesystem::from_transcript(&regenerated).unwrap();
+            prop_assert_eq!(
+                reparsed.sizes().iter().collect::<Vec<_>>(),
+                filesystem.sizes().iter().collect::<Vec<_>>()
+            );
+        }
+    }
+}
@@ -1,44 +1,88 @@
-use std::str::Lines;
-
 // Parse the instructions one by one
 // Create "dir" entries with a parent and contents
 // Turn those into a tree
 // Figure out how to parse them
-use camino::Utf8PathBuf;
-use id_tree::{InsertBehavior, Node, Tree};
+pub mod disk;
+pub mod report;
+
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BTreeMap, HashMap},
+};
+
+use aoc_common::{
+    parse::{parse_lines, ParseError},
+    Answer, Solution,
+};
+use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
+use color_eyre::eyre::{bail, eyre, WrapErr};
+use id_tree::{InsertBehavior, Node, NodeId, Tree};
 use nom::{
     branch::alt,
-    bytes::complete::{tag, take_while1},
-    combinator::{all_consuming, map},
-    sequence::{preceded, separated_pair},
+    bytes::complete::{tag, take_till1, take_while1},
+    character::complete::{alphanumeric1, digit1, one_of, space0, space1},
+    combinator::{all_consuming, map, opt, recognize, rest, verify},
+    multi::many0,
+    sequence::{pair, preceded, separated_pair, terminated, tuple},
     Finish, IResult,
 };
+use serde::Serialize;
 
+/// Everything up to the end of the line, so names may contain spaces
 fn parse_path(i: &str) -> IResult<&str, Utf8PathBuf> {
-    map(
-        take_while1(|c: char| "abcdefghijklmnopqrstuvwxyz./".contains(c)),
-        Into::into,
-    )(i)
+    map(verify(rest, |s: &str| !s.trim().is_empty()), |s: &str| {
+        s.trim_end().into()
+    })(i)
+}
+
+/// A listed entry is a single name inside the current directory, never a path
+fn parse_name(i: &str) -> IResult<&str, Utf8PathBuf> {
+    verify(parse_path, |name: &Utf8PathBuf| {
+        !name.as_str().contains('/') && name != "." && name != ".."
+    })(i)
 }
 
 #[derive(Debug)]
 pub struct Ls;
 
+/// `ls` with optional flags such as `-la` or `--color=auto`; listing another
+/// directory is not supported
 fn parse_ls(i: &str) -> IResult<&str, Ls> {
-    map(tag("ls"), |_| Ls)(i)
+    let long_flag = preceded(
+        tag("--"),
+        recognize(pair(
+            take_while1(|c: char| c.is_ascii_alphanumeric() || c == '-'),
+            opt(pair(tag("="), take_till1(|c: char| c == ' '))),
+        )),
+    );
+    let flag = alt((long_flag, preceded(tag("-"), alphanumeric1)));
+    map(
+        terminated(tag("ls"), pair(many0(preceded(space1, flag)), space0)),
+        |_| Ls,
+    )(i)
 }
 
 #[derive(Debug)]
 pub struct Cd(Utf8PathBuf);
 
 fn parse_cd(i: &str) -> IResult<&str, Cd> {
-    map(preceded(tag("cd "), parse_path), Cd)(i)
+    map(preceded(pair(tag("cd"), space1), parse_path), Cd)(i)
+}
+
+#[derive(Debug)]
+pub struct Pwd;
+
+fn parse_pwd(i: &str) -> IResult<&str, Pwd> {
+    map(terminated(tag("pwd"), space0), |_| Pwd)(i)
 }
 
 #[derive(Debug)]
 pub enum Command {
     Ls,
     Cd(Utf8PathBuf),
+    Pwd,
+    /// Any other command, whose output is skipped
+    Unknown(String),
 }
 
 impl From<Ls> for Command {
@@ -53,17 +97,74 @@ impl From<Cd> for Command {
     }
 }
 
+impl From<Pwd> for Command {
+    fn from(_pwd: Pwd) -> Self {
+        Command::Pwd
+    }
+}
+
 fn parse_command(i: &str) -> IResult<&str, Command> {
     let (i, _) = tag("$ ")(i)?;
-    alt((map(parse_ls, Into::into), map(parse_cd, Into::into)))(i)
+    alt((
+        map(all_consuming(parse_ls), Into::into),
+        map(all_consuming(parse_cd), Into::into),
+        map(all_consuming(parse_pwd), Into::into),
+        map(rest, |command: &str| {
+            Command::Unknown(command.trim().to_owned())
+        }),
+    ))(i)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeKind {
+    Dir,
+    File,
 }
 
 #[derive(Debug)]
-struct TreeNode {
+pub struct TreeNode {
+    /// Absolute path, e.g. `/a/e`
     path: Utf8PathBuf,
+    kind: NodeKind,
     size: u64,
 }
 
+impl TreeNode {
+    fn dir(path: Utf8PathBuf) -> Self {
+        Self {
+            path,
+            kind: NodeKind::Dir,
+            size: 0,
+        }
+    }
+
+    fn file(path: Utf8PathBuf, size: u64) -> Self {
+        Self {
+            path,
+            kind: NodeKind::File,
+            size,
+        }
+    }
+
+    pub fn path(&self) -> &Utf8Path {
+        &self.path
+    }
+
+    /// Own size for files, zero for directories
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn kind(&self) -> NodeKind {
+        self.kind
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.kind == NodeKind::Dir
+    }
+}
+
 #[derive(Debug)]
 enum FilesystemEntry {
     Dir(Utf8PathBuf),
@@ -72,130 +173,621 @@ enum FilesystemEntry {
 
 fn parse_entry(i: &str) -> IResult<&str, FilesystemEntry> {
     let parse_file = map(
-        separated_pair(nom::character::complete::u64, tag(" "), parse_path),
+        separated_pair(nom::character::complete::u64, space1, parse_name),
         |(size, path)| FilesystemEntry::File(size, path),
     );
-    let parse_dir = map(preceded(tag("dir "), parse_path), FilesystemEntry::Dir);
+    let parse_dir = map(
+        preceded(pair(tag("dir"), space1), parse_name),
+        FilesystemEntry::Dir,
+    );
 
     alt((parse_file, parse_dir))(i)
 }
+
+/// A size column of `ls -l`, which `ls -lh` rounds to units like `4.0K`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ListedSize<'a> {
+    Bytes(u64),
+    Rounded(&'a str),
+}
+
+fn parse_listed_size(i: &str) -> IResult<&str, ListedSize<'_>> {
+    let rounded = recognize(tuple((
+        digit1,
+        opt(pair(tag("."), digit1)),
+        one_of("KMGTPE"),
+    )));
+    alt((
+        map(rounded, ListedSize::Rounded),
+        map(nom::character::complete::u64, ListedSize::Bytes),
+    ))(i)
+}
+
+/// A line of `ls -l` output, e.g. `-rw-r--r-- 1 user staff 584 Dec  7 10:00 i`.
+/// Entries that are neither files nor directories are skipped.
+fn parse_long_entry(i: &str) -> IResult<&str, ParsedLine> {
+    let field = |i| preceded(space1, take_till1(|c: char| c == ' '))(i);
+    let (i, permissions) = verify(take_till1(|c: char| c == ' '), |p: &str| {
+        p.len() >= 10 && p.starts_with(['-', 'd', 'l', 'b', 'c', 'p', 's'])
+    })(i)?;
+    let (i, _links_owner_group) = tuple((field, field, field))(i)?;
+    let (i, size) = preceded(space1, parse_listed_size)(i)?;
+    let (i, _date) = tuple((field, field, field))(i)?;
+    let (i, _) = space1(i)?;
+
+    // `ls -a` lists the directory itself and its parent, which aren't entries
+    if permissions.starts_with('d') && matches!(i.trim_end(), "." | "..") {
+        return Ok(("", ParsedLine::Skipped));
+    }
+
+    let (i, name) = parse_name(i)?;
+    let line = match (permissions.as_bytes()[0], size) {
+        (b'd', _) => ParsedLine::Entry(FilesystemEntry::Dir(name)),
+        (b'-', ListedSize::Bytes(size)) => ParsedLine::Entry(FilesystemEntry::File(size, name)),
+        (b'-', ListedSize::Rounded(size)) => ParsedLine::RoundedFile {
+            name,
+            size: size.to_owned(),
+        },
+        _ => ParsedLine::Skipped,
+    };
+    Ok((i, line))
+}
+
 #[derive(Debug)]
 enum ParsedLine {
     Command(Command),
     Entry(FilesystemEntry),
+    /// A file listed by `ls -lh`, whose exact size is unknown
+    RoundedFile {
+        name: Utf8PathBuf,
+        size: String,
+    },
+    /// Output that carries no information about the tree
+    Skipped,
 }
 
-fn parse_line(i: &str) -> IResult<&str, ParsedLine> {
+/// What non-command lines are expected to be, based on the previous command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Output {
+    None,
+    Listing,
+    Ignored,
+}
+
+fn parse_listing_line(i: &str) -> IResult<&str, ParsedLine> {
     alt((
-        map(parse_command, ParsedLine::Command),
+        parse_long_entry,
         map(parse_entry, ParsedLine::Entry),
+        map(tuple((tag("total"), space1, parse_listed_size)), |_| {
+            ParsedLine::Skipped
+        }),
     ))(i)
 }
 
-fn subtree_size(tree: &Tree<TreeNode>, node: &Node<TreeNode>) -> color_eyre::Result<u64> {
-    let mut total = node.data().size;
-    for child in node.children() {
-        total += subtree_size(tree, tree.get(child)?)?;
+/// A directory tree reconstructed from a terminal transcript of `cd` and `ls` commands
+#[derive(Debug)]
+pub struct Filesystem {
+    tree: Tree<TreeNode>,
+    nodes: NodeIndex,
+    sizes: SizeIndex,
+    warnings: Vec<String>,
+}
+
+impl Filesystem {
+    pub fn from_transcript(input: &str) -> color_eyre::Result<Self> {
+        let (tree, nodes, warnings) = construct_tree(input)?;
+        let sizes = SizeIndex::new(&tree)?;
+
+        Ok(Self {
+            tree,
+            nodes,
+            sizes,
+            warnings,
+        })
     }
-    Ok(total)
+
+    /// Lines of the transcript that were skipped, e.g. unknown commands
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// The file or directory at an absolute path
+    pub fn get(&self, path: impl AsRef<Utf8Path>) -> Option<&TreeNode> {
+        let id = self.nodes.get(path.as_ref())?;
+        self.tree.get(id).ok().map(Node::data)
+    }
+
+    /// The size of a file, or the cumulative size of a directory
+    pub fn size_of(&self, path: impl AsRef<Utf8Path>) -> Option<u64> {
+        let node = self.get(&path)?;
+        match node.kind {
+            NodeKind::Dir => self.sizes.get(path),
+            NodeKind::File => Some(node.size),
+        }
+    }
+
+    pub fn tree(&self) -> &Tree<TreeNode> {
+        &self.tree
+    }
+
+    pub fn sizes(&self) -> &SizeIndex {
+        &self.sizes
+    }
+}
+
+/// Cumulative size of every directory, keyed by its full path
+#[derive(Debug, Default)]
+pub struct SizeIndex {
+    sizes: BTreeMap<Utf8PathBuf, u64>,
 }
 
-fn construct_tree() -> color_eyre::Result<Tree<TreeNode>> {
-    color_eyre::install()?;
+impl SizeIndex {
+    /// Computes every directory size in a single post-order pass
+    pub fn new(tree: &Tree<TreeNode>) -> color_eyre::Result<Self> {
+        let mut index = Self::default();
+        if let Some(root_id) = tree.root_node_id() {
+            index.insert_subtree(tree, root_id)?;
+        }
+        Ok(index)
+    }
 
-    let input = include_str!("pt1.txt");
-    let parsed_lines = input
-        .lines()
-        .map(|l| all_consuming(parse_line)(l).finish().unwrap().1);
+    fn insert_subtree(&mut self, tree: &Tree<TreeNode>, id: &NodeId) -> color_eyre::Result<u64> {
+        let node = tree.get(id)?;
+        let mut total = node.data().size;
+        for child_id in node.children() {
+            total += self.insert_subtree(tree, child_id)?;
+        }
+
+        if node.data().is_dir() {
+            self.sizes.insert(node.data().path.clone(), total);
+        }
+        Ok(total)
+    }
+
+    /// The size of the directory at `path`
+    pub fn get(&self, path: impl AsRef<Utf8Path>) -> Option<u64> {
+        self.sizes.get(path.as_ref()).copied()
+    }
+
+    /// All directories with their sizes, ordered by path
+    pub fn iter(&self) -> impl Iterator<Item = (&Utf8Path, u64)> {
+        self.sizes
+            .iter()
+            .map(|(path, &size)| (path.as_path(), size))
+    }
+
+    /// Total space used, i.e. the size of `/`
+    pub fn total_used(&self) -> u64 {
+        self.get("/").unwrap_or(0)
+    }
+
+    /// Directories whose size is at most `threshold`
+    pub fn at_most(&self, threshold: u64) -> impl Iterator<Item = (&Utf8Path, u64)> {
+        self.iter().filter(move |&(_, size)| size <= threshold)
+    }
+
+    /// The smallest directory that frees at least `needed` bytes when deleted
+    pub fn smallest_freeing(&self, needed: u64) -> Option<(&Utf8Path, u64)> {
+        self.iter()
+            .filter(|&(_, size)| size >= needed)
+            .min_by_key(|&(_, size)| size)
+    }
+
+    /// The `k` largest directories, largest first
+    pub fn largest(&self, k: usize) -> Vec<(&Utf8Path, u64)> {
+        let mut dirs = self.iter().collect::<Vec<_>>();
+        dirs.sort_by_key(|&(path, size)| (Reverse(size), path));
+        dirs.truncate(k);
+        dirs
+    }
+}
+
+/// Returns the directory `name` under `parent`, creating it if it was never seen
+fn get_or_insert_dir(
+    tree: &mut Tree<TreeNode>,
+    nodes: &mut NodeIndex,
+    parent: &NodeId,
+    name: &Utf8Path,
+) -> color_eyre::Result<NodeId> {
+    let path = tree.get(parent)?.data().path.join(name);
+    match nodes.get(&path) {
+        Some(existing) if tree.get(existing)?.data().is_dir() => Ok(existing.clone()),
+        Some(_) => Err(eyre!("`{path}` is a file, not a directory")),
+        None => {
+            let id = tree.insert(
+                Node::new(TreeNode::dir(path.clone())),
+                InsertBehavior::UnderNode(parent),
+            )?;
+            nodes.insert(path, id.clone());
+            Ok(id)
+        }
+    }
+}
+
+/// Every node indexed by its absolute path while the tree is built, so finding
+/// an existing entry doesn't scan its siblings
+type NodeIndex = HashMap<Utf8PathBuf, NodeId>;
+
+fn construct_tree(input: &str) -> color_eyre::Result<(Tree<TreeNode>, NodeIndex, Vec<String>)> {
+    let mut output = Output::None;
+    let parsed_lines = parse_lines(input, |l| {
+        if let Ok((_, command)) = parse_command(l) {
+            output = match command {
+                Command::Ls => Output::Listing,
+                Command::Cd(_) => Output::None,
+                Command::Pwd | Command::Unknown(_) => Output::Ignored,
+            };
+            return Ok(ParsedLine::Command(command));
+        }
+
+        match output {
+            Output::Ignored => Ok(ParsedLine::Skipped),
+            Output::Listing => all_consuming(parse_listing_line)(l)
+                .finish()
+                .map(|(_, parsed_line)| parsed_line)
+                .map_err(|err| ParseError::at(l, err.input, "a command or a directory entry")),
+            Output::None => Err(ParseError::at(l, l, "a command")),
+        }
+    })
+    .map_err(ParseError::into_report)?;
 
     let mut tree = Tree::<TreeNode>::new();
 
     let root = tree.insert(
-        Node::new(TreeNode {
-            path: "/".into(),
-            size: 0,
-        }),
+        Node::new(TreeNode::dir("/".into())),
         id_tree::InsertBehavior::AsRoot,
     )?;
+    let mut nodes = NodeIndex::from([("/".into(), root.clone())]);
 
     // Let the state begin
-    let mut curr = root;
-    for parsed_line in parsed_lines {
-        println!("{parsed_line:?}");
-
+    let mut curr = root.clone();
+    let mut warnings = vec![];
+    for (line_number, parsed_line) in parsed_lines.into_iter().enumerate() {
+        let line = line_number + 1;
         match parsed_line {
             ParsedLine::Command(cmd) => match cmd {
-                Command::Ls => {}
-                Command::Cd(path) => match path.as_str() {
-                    "/" => {
-                        // ignore, starting here
-                    }
-                    ".." => {
-                        curr = tree.get(&curr)?.parent().unwrap().clone();
+                Command::Ls | Command::Pwd => {}
+                Command::Cd(path) => {
+                    for component in path.components() {
+                        curr = match component {
+                            Utf8Component::RootDir => root.clone(),
+                            Utf8Component::CurDir => curr,
+                            Utf8Component::ParentDir => tree
+                                .get(&curr)?
+                                .parent()
+                                .ok_or_else(|| eyre!("line {line}: cannot `cd ..` from /"))?
+                                .clone(),
+                            Utf8Component::Normal(name) => {
+                                get_or_insert_dir(&mut tree, &mut nodes, &curr, name.into())
+                                    .wrap_err_with(|| format!("line {line}: cannot `cd {path}`"))?
+                            }
+                            Utf8Component::Prefix(_) => {
+                                bail!("line {line}: unsupported path `{path}`")
+                            }
+                        };
                     }
-                    _ => {
-                        let node = Node::new(TreeNode {
-                            path: path.clone(),
-                            size: 0,
-                        });
-                        curr = tree.insert(node, id_tree::InsertBehavior::UnderNode(&curr))?;
-                    }
-                },
+                }
+                Command::Unknown(command) => {
+                    warnings.push(format!("line {line}: skipped unknown command `{command}`"))
+                }
             },
+            ParsedLine::Skipped => {}
+            ParsedLine::RoundedFile { name, size } => warnings.push(format!(
+                "line {line}: skipped `{name}`, its size `{size}` is rounded (drop `-h` from `ls`)"
+            )),
             ParsedLine::Entry(entry) => match entry {
-                FilesystemEntry::Dir(_) => {
-                    // ignore, we'll do that when we `cd` into it
+                FilesystemEntry::Dir(path) => {
+                    get_or_insert_dir(&mut tree, &mut nodes, &curr, &path)
+                        .wrap_err_with(|| format!("line {line}: cannot add `dir {path}`"))?;
                 }
-                FilesystemEntry::File(size, path) => {
-                    let node = Node::new(TreeNode { size, path });
-                    tree.insert(node, InsertBehavior::UnderNode(&curr))?;
+                FilesystemEntry::File(size, name) => {
+                    let path = tree.get(&curr)?.data().path.join(name);
+                    // Listing a directory again must not count its files twice
+                    if let Entry::Vacant(entry) = nodes.entry(path) {
+                        let node = Node::new(TreeNode::file(entry.key().clone(), size));
+                        entry.insert(tree.insert(node, InsertBehavior::UnderNode(&curr))?);
+                    }
                 }
             },
         }
     }
 
-    let mut s = String::new();
-    tree.write_formatted(&mut s)?;
-    println!("{s}");
-
-    Ok(tree)
+    Ok((tree, nodes, warnings))
 }
 
-fn main() {
-    let tree = construct_tree().unwrap();
+const DISK_SIZE: u64 = 70000000;
+const UPDATE_SIZE: u64 = 30000000;
 
-    let total_size = 70000000;
-    let needed_size = 30000000;
-    let current_useage =
-        subtree_size(&tree, tree.get(tree.root_node_id().unwrap()).unwrap()).unwrap();
+pub struct Day07;
 
-    let free_size = total_size - current_useage;
+impl Solution for Day07 {
+    const DAY: u8 = 7;
 
-    let size_to_free = needed_size - free_size;
+    type Parsed = Filesystem;
 
-    let smallest_size_to_remove = tree
-        .traverse_pre_order(tree.root_node_id().unwrap())
-        .unwrap()
-        .filter(|n| !n.children().is_empty()) // Keep only directories
-        .map(|n| subtree_size(&tree, &n).unwrap())
-        .filter(|&n| n > size_to_free)
-        .inspect(|s| {
-            dbg!(s);
-        })
-        .min();
+    fn parse(input: &str) -> color_eyre::Result<Self::Parsed> {
+        Filesystem::from_transcript(input)
+    }
+
+    fn warnings(filesystem: &Self::Parsed) -> Vec<String> {
+        filesystem.warnings().to_vec()
+    }
+
+    fn part1(filesystem: &Self::Parsed) -> color_eyre::Result<Answer> {
+        let total = filesystem
+            .sizes()
+            .at_most(100000)
+            .map(|(_, size)| size)
+            .sum::<u64>();
 
-    dbg!(smallest_size_to_remove);
+        Ok(total.into())
+    }
+
+    fn part2(filesystem: &Self::Parsed) -> color_eyre::Result<Answer> {
+        let sizes = filesystem.sizes();
+        let free_size = DISK_SIZE.saturating_sub(sizes.total_used());
+        let size_to_free = UPDATE_SIZE.saturating_sub(free_size);
+
+        sizes
+            .smallest_freeing(size_to_free)
+            .map(|(_, size)| size.into())
+            .ok_or_else(|| color_eyre::eyre::eyre!("No directory is large enough"))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use camino::Utf8Path;
-    use nom::{combinator::all_consuming, Finish};
+    use aoc_common::{parse::ParseError, Answer, Solution};
 
-    use crate::{main, parse_line};
+    use crate::{Day07, Filesystem, NodeKind};
 
     #[test]
     fn test_starting_input() {
-        main()
+        let input = include_str!("pt1.txt");
+        let tree = Day07::parse(input).unwrap();
+        Day07::part2(&tree).unwrap();
+    }
+
+    #[test]
+    fn test_from_transcript() {
+        let input = include_str!("starting_input.txt");
+
+        // Building twice must work, nothing global is touched
+        for _ in 0..2 {
+            let filesystem = Filesystem::from_transcript(input).unwrap();
+            let tree = filesystem.tree();
+            let root = tree.get(tree.root_node_id().unwrap()).unwrap();
+            assert_eq!(root.children().len(), 4);
+        }
+    }
+
+    #[test]
+    fn test_revisited_directories() {
+        let input = "$ cd /
+$ ls
+dir a
+100 b
+$ cd a
+$ ls
+10 c
+$ cd /
+$ ls
+dir a
+100 b
+$ cd a
+$ ls
+10 c
+$ cd ..
+$ cd a
+$ ls
+10 c";
+        let filesystem = Filesystem::from_transcript(input).unwrap();
+        let tree = filesystem.tree();
+        let root = tree.get(tree.root_node_id().unwrap()).unwrap();
+
+        assert_eq!(root.children().len(), 2);
+        assert_eq!(filesystem.sizes().total_used(), 110);
+    }
+
+    #[test]
+    fn test_empty_directories() {
+        // `b` is never entered and `a` only holds an empty directory
+        let input = "$ cd /
+$ ls
+dir a
+dir b
+$ cd a
+$ ls
+dir c";
+        let filesystem = Filesystem::from_transcript(input).unwrap();
+        let tree = filesystem.tree();
+        let root_id = tree.root_node_id().unwrap();
+
+        let kinds = tree
+            .traverse_pre_order(root_id)
+            .unwrap()
+            .map(|node| node.data().kind())
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, vec![NodeKind::Dir; 4]);
+        assert_eq!(
+            filesystem.sizes().iter().collect::<Vec<_>>(),
+            vec![
+                ("/".into(), 0),
+                ("/a".into(), 0),
+                ("/a/c".into(), 0),
+                ("/b".into(), 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_real_shell_transcript() {
+        let input = "$ cd /
+$ pwd
+/
+$ ls -la
+total 24
+drwxr-xr-x  4 user staff  128 Dec  7 10:00 .
+drwxr-xr-x 12 root wheel  384 Dec  1 09:00 ..
+drwxr-xr-x  3 user staff   96 Dec  7 10:00 My Photos
+-rw-r--r--  1 user staff 2048 Dec  7 10:00 notes-2022.TXT
+lrwxr-xr-x  1 user staff    9 Dec  7 10:00 latest -> My Photos
+$ git status
+On branch main
+nothing to commit, working tree clean
+$ cd My Photos
+$ ls
+1000 IMG_0001.jpg
+dir raw-2022
+$ cd /My Photos/raw-2022
+$ ls -l
+-rw-r--r--  1 user staff 10 Dec  7 10:00 a.raw";
+        let filesystem = Filesystem::from_transcript(input).unwrap();
+
+        assert_eq!(filesystem.size_of("/notes-2022.TXT"), Some(2048));
+        assert_eq!(filesystem.size_of("/My Photos"), Some(1010));
+        assert_eq!(filesystem.size_of("/My Photos/raw-2022/a.raw"), Some(10));
+        assert!(filesystem.get("/latest").is_none());
+        assert_eq!(
+            filesystem.warnings(),
+            ["line 11: skipped unknown command `git status`"]
+        );
+
+        // `ls -lh` rounds sizes, so those files can't be counted exactly
+        let input = "$ cd /
+$ ls -lh
+total 8.0K
+drwxr-xr-x 2 user staff 4.0K Dec  7 10:00 src
+-rw-r--r-- 1 user staff 1.5K Dec  7 10:00 README.md
+-rw-r--r-- 1 user staff  584 Dec  7 10:00 i";
+        let filesystem = Filesystem::from_transcript(input).unwrap();
+
+        assert!(filesystem.get("/src").unwrap().is_dir());
+        assert!(filesystem.get("/README.md").is_none());
+        assert_eq!(filesystem.size_of("/"), Some(584));
+        assert_eq!(
+            filesystem.warnings(),
+            ["line 5: skipped `README.md`, its size `1.5K` is rounded (drop `-h` from `ls`)"]
+        );
+
+        let input = "$ cd /
+$ ls --color=auto
+dir src
+1024 Cargo.toml
+$ cd src
+$ ls -l --group-directories-first
+-rw-r--r-- 1 user staff 300 Dec  7 10:00 main.rs";
+        let filesystem = Filesystem::from_transcript(input).unwrap();
+
+        assert_eq!(filesystem.size_of("/"), Some(1324));
+        assert!(filesystem.warnings().is_empty());
+    }
+
+    #[test]
+    fn test_output_without_command() {
+        let err = Day07::parse("$ cd /\n10 a").unwrap_err();
+        let err = err.downcast::<ParseError>().unwrap();
+        assert_eq!((err.line, err.expected.as_str()), (2, "a command"));
+    }
+
+    #[test]
+    fn test_entry_names_are_single_components() {
+        for entry in [
+            "5 ../escaped.txt",
+            "5 ..",
+            "dir .",
+            "dir a/b",
+            "-rw-r--r-- 1 user staff 5 Dec  7 10:00 ..",
+            "drwxr-xr-x 2 user staff 64 Dec  7 10:00 a/b",
+        ] {
+            let err = Day07::parse(&format!("$ cd /\n$ ls\n{entry}")).unwrap_err();
+            let err = err.downcast::<ParseError>().unwrap();
+            assert_eq!(err.line, 3, "{entry}");
+        }
+    }
+
+    #[test]
+    fn test_cd_into_file() {
+        let err = Filesystem::from_transcript("$ cd /\n$ ls\n10 a\n$ cd a").unwrap_err();
+        assert_eq!(err.to_string(), "line 4: cannot `cd a`");
+        assert_eq!(
+            err.root_cause().to_string(),
+            "`/a` is a file, not a directory"
+        );
+    }
+
+    #[test]
+    fn test_cd_above_root() {
+        let err = Filesystem::from_transcript("$ cd /\n$ cd ..").unwrap_err();
+        assert_eq!(err.to_string(), "line 2: cannot `cd ..` from /");
+    }
+
+    #[test]
+    fn test_invalid_line() {
+        let err = Day07::parse("$ cd /\n$ ls\nfoo bar").unwrap_err();
+        let err = err.downcast::<ParseError>().unwrap();
+        assert_eq!((err.line, err.column), (3, 1));
+    }
+
+    const EXAMPLE: &str = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k";
+
+    #[test]
+    fn test_example() {
+        let tree = Day07::parse(EXAMPLE).unwrap();
+        assert_eq!(Day07::part1(&tree).unwrap(), Answer::Number(95437));
+        assert_eq!(Day07::part2(&tree).unwrap(), Answer::Number(24933642));
+    }
+
+    #[test]
+    fn test_path_lookups() {
+        let filesystem = Filesystem::from_transcript(EXAMPLE).unwrap();
+
+        let e = filesystem.get("/a/e").unwrap();
+        assert_eq!((e.path().as_str(), e.kind()), ("/a/e", NodeKind::Dir));
+        assert_eq!(filesystem.size_of("/d"), Some(24933642));
+        assert_eq!(filesystem.size_of("/a/e/i"), Some(584));
+        assert!(filesystem.get("/e").is_none());
+    }
+
+    #[test]
+    fn test_size_queries() {
+        let filesystem = Filesystem::from_transcript(EXAMPLE).unwrap();
+        let sizes = filesystem.sizes();
+
+        assert_eq!(sizes.total_used(), 48381165);
+        assert_eq!(
+            sizes.at_most(100000).collect::<Vec<_>>(),
+            vec![("/a".into(), 94853), ("/a/e".into(), 584)]
+        );
+        assert_eq!(
+            sizes.smallest_freeing(8381165),
+            Some(("/d".into(), 24933642))
+        );
+        assert_eq!(
+            sizes.largest(2),
+            vec![("/".into(), 48381165), ("/d".into(), 24933642)]
+        );
     }
 }
@@ -0,0 +1,271 @@
+//! Human-readable and JSON views of a reconstructed [`Filesystem`].
+
+use id_tree::NodeId;
+use serde::Serialize;
+
+use crate::{Filesystem, NodeKind, TreeNode};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReportOptions {
+    /// Only show entries this many levels below `/`
+    pub max_depth: Option<usize>,
+    /// Print sizes like `47M` instead of raw byte counts
+    pub human_readable: bool,
+}
+
+/// Formats a byte count with binary units, rounding up the way `du -h` does
+pub fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["K", "M", "G", "T", "P"];
+
+    if bytes < 1024 {
+        return bytes.to_string();
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    // Small sizes keep one decimal, and rounding up can reach the next unit
+    size = if size < 10.0 {
+        (size * 10.0).ceil() / 10.0
+    } else {
+        size.ceil()
+    };
+    if size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if size < 10.0 {
+        format!("{size:.1}{}", UNITS[unit])
+    } else {
+        format!("{size:.0}{}", UNITS[unit])
+    }
+}
+
+/// One line per directory, children before their parent, like `du`
+pub fn du(filesystem: &Filesystem, options: &ReportOptions) -> String {
+    let mut lines = vec![];
+    if let Some(root_id) = filesystem.tree.root_node_id() {
+        du_lines(filesystem, root_id, 0, options, &mut lines);
+    }
+    lines.join("\n")
+}
+
+fn du_lines(
+    filesystem: &Filesystem,
+    id: &NodeId,
+    depth: usize,
+    options: &ReportOptions,
+    lines: &mut Vec<String>,
+) {
+    if options.max_depth.is_some_and(|max| depth > max) {
+        return;
+    }
+
+    for child_id in sorted_children(filesystem, id) {
+        if node(filesystem, &child_id).is_dir() {
+            du_lines(filesystem, &child_id, depth + 1, options, lines);
+        }
+    }
+
+    let node = node(filesystem, id);
+    lines.push(format!(
+        "{}\t{}",
+        size(filesystem, id, options),
+        node.path()
+    ));
+}
+
+/// Every entry drawn as an indented tree, like `tree --du`
+pub fn tree(filesystem: &Filesystem, options: &ReportOptions) -> String {
+    let mut lines = vec![];
+    if let Some(root_id) = filesystem.tree.root_node_id() {
+        lines.push(format!("/ ({})", size(filesystem, root_id, options)));
+        tree_lines(filesystem, root_id, "", 1, options, &mut lines);
+    }
+    lines.join("\n")
+}
+
+fn tree_lines(
+    filesystem: &Filesystem,
+    id: &NodeId,
+    prefix: &str,
+    depth: usize,
+    options: &ReportOptions,
+    lines: &mut Vec<String>,
+) {
+    if options.max_depth.is_some_and(|max| depth > max) {
+        return;
+    }
+
+    let children = sorted_children(filesystem, id);
+    for (i, child_id) in children.iter().enumerate() {
+        let last = i == children.len() - 1;
+        let (branch, indent) = if last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+
+        let child = node(filesystem, child_id);
+        let suffix = if child.is_dir() { "/" } else { "" };
+        lines.push(format!(
+            "{prefix}{branch}{}{suffix} ({})",
+            name(child),
+            size(filesystem, child_id, options)
+        ));
+
+        if child.is_dir() {
+            let prefix = format!("{prefix}{indent}");
+            tree_lines(filesystem, child_id, &prefix, depth + 1, options, lines);
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct JsonNode<'a> {
+    name: &'a str,
+    path: &'a str,
+    kind: NodeKind,
+    size: u64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    children: Vec<JsonNode<'a>>,
+}
+
+/// The whole tree with cumulative sizes, as pretty-printed JSON
+pub fn to_json(filesystem: &Filesystem) -> serde_json::Result<String> {
+    match filesystem.tree.root_node_id() {
+        Some(root_id) => serde_json::to_string_pretty(&json_node(filesystem, root_id)),
+        None => Ok("null".to_string()),
+    }
+}
+
+fn json_node<'a>(filesystem: &'a Filesystem, id: &NodeId) -> JsonNode<'a> {
+    let node = node(filesystem, id);
+    JsonNode {
+        name: name(node),
+        path: node.path().as_str(),
+        kind: node.kind(),
+        size: filesystem.size_of(node.path()).unwrap_or(0),
+        children: sorted_children(filesystem, id)
+            .iter()
+            .map(|child_id| json_node(filesystem, child_id))
+            .collect(),
+    }
+}
+
+fn node<'a>(filesystem: &'a Filesystem, id: &NodeId) -> &'a TreeNode {
+    filesystem
+        .tree
+        .get(id)
+        .expect("node ids come from the same tree")
+        .data()
+}
+
+fn name(node: &TreeNode) -> &str {
+    node.path().file_name().unwrap_or("/")
+}
+
+fn size(filesystem: &Filesystem, id: &NodeId, options: &ReportOptions) -> String {
+    let bytes = filesystem.size_of(node(filesystem, id).path()).unwrap_or(0);
+    if options.human_readable {
+        human_size(bytes)
+    } else {
+        bytes.to_string()
+    }
+}
+
+/// Children ordered largest first, then by name
+fn sorted_children(filesystem: &Filesystem, id: &NodeId) -> Vec<NodeId> {
+    let mut children = filesystem
+        .tree
+        .children_ids(id)
+        .map(|ids| ids.cloned().collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    children.sort_by_cached_key(|child_id| {
+        let child = node(filesystem, child_id);
+        let size = filesystem.size_of(child.path()).unwrap_or(0);
+        (std::cmp::Reverse(size), child.path().to_owned())
+    });
+    children
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMALL: &str = "$ cd /
+$ ls
+dir a
+2048 b.txt
+$ cd a
+$ ls
+dir c
+10 d
+$ cd c
+$ ls
+5 e";
+
+    #[test]
+    fn test_human_size() {
+        assert_eq!(human_size(584), "584");
+        assert_eq!(human_size(94853), "93K");
+        assert_eq!(human_size(2048), "2.0K");
+        assert_eq!(human_size(8504156), "8.2M");
+        assert_eq!(human_size(48381165), "47M");
+        assert_eq!(human_size(1024 * 1024 - 1), "1.0M");
+    }
+
+    #[test]
+    fn test_du() {
+        let filesystem = Filesystem::from_transcript(SMALL).unwrap();
+
+        assert_eq!(
+            du(&filesystem, &ReportOptions::default()),
+            "5\t/a/c\n15\t/a\n2063\t/"
+        );
+
+        let options = ReportOptions {
+            max_depth: Some(1),
+            human_readable: true,
+        };
+        assert_eq!(du(&filesystem, &options), "15\t/a\n2.1K\t/");
+    }
+
+    #[test]
+    fn test_tree() {
+        let filesystem = Filesystem::from_transcript(SMALL).unwrap();
+
+        assert_eq!(
+            tree(&filesystem, &ReportOptions::default()),
+            "/ (2063)
+├── b.txt (2048)
+└── a/ (15)
+    ├── d (10)
+    └── c/ (5)
+        └── e (5)"
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let filesystem = Filesystem::from_transcript("$ cd /\n$ ls\n10 a").unwrap();
+        let json: serde_json::Value = serde_json::from_str(&to_json(&filesystem).unwrap()).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "name": "/",
+                "path": "/",
+                "kind": "dir",
+                "size": 10,
+                "children": [{ "name": "a", "path": "/a", "kind": "file", "size": 10 }]
+            })
+        );
+    }
+}
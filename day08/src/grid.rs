@@ -1,6 +1,8 @@
-use std::fmt::Debug;
+use std::fmt::{self, Debug, Display};
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+use aoc_common::parse::{ParseError, ParseResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct GridCoord {
     pub x: usize,
     pub y: usize,
@@ -11,41 +13,236 @@ impl From<(usize, usize)> for GridCoord {
     }
 }
 
+impl GridCoord {
+    /// One step in `direction`, or `None` when that would go below zero
+    pub fn step(self, direction: Direction) -> Option<Self> {
+        let (dx, dy) = direction.delta();
+        Some(Self {
+            x: self.x.checked_add_signed(dx)?,
+            y: self.y.checked_add_signed(dy)?,
+        })
+    }
+}
+
+/// Directions on screen, so `Up` decreases `y`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl Direction {
+    pub const CARDINAL: [Direction; 4] = [Self::Up, Self::Left, Self::Right, Self::Down];
+    pub const ALL: [Direction; 8] = [
+        Self::UpLeft,
+        Self::Up,
+        Self::UpRight,
+        Self::Left,
+        Self::Right,
+        Self::DownLeft,
+        Self::Down,
+        Self::DownRight,
+    ];
+
+    /// The `(dx, dy)` of one step
+    pub fn delta(self) -> (isize, isize) {
+        match self {
+            Self::Up => (0, -1),
+            Self::Down => (0, 1),
+            Self::Left => (-1, 0),
+            Self::Right => (1, 0),
+            Self::UpLeft => (-1, -1),
+            Self::UpRight => (1, -1),
+            Self::DownLeft => (-1, 1),
+            Self::DownRight => (1, 1),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
 pub struct Grid<T> {
     pub width: usize,
     pub height: usize,
     data: Vec<T>,
 }
 
-impl Debug for Grid<usize> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut row_strings: Vec<String> = vec![String::new()];
+/// A rearrangement of a whole grid, see [`Grid::transformed`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transform {
+    /// Swap rows and columns
+    Transpose,
+    /// Rotate clockwise by 90 degrees
+    Rotate90,
+    Rotate180,
+    /// Rotate clockwise by 270 degrees, i.e. counter-clockwise by 90
+    Rotate270,
+    /// Mirror left to right
+    FlipHorizontal,
+    /// Mirror top to bottom
+    FlipVertical,
+}
 
-        for row in 0..self.height {
-            let start_index = row * self.width;
-            let end_index = (row + 1) * self.width;
-            let row_vec: Vec<String> = self.data[start_index..end_index]
-                .iter()
-                .map(|n| u32::try_from(n.clone()).unwrap().to_string())
-                .collect();
+impl Transform {
+    /// The dimensions after transforming a `width` by `height` grid
+    pub fn dimensions(self, width: usize, height: usize) -> (usize, usize) {
+        match self {
+            Self::Transpose | Self::Rotate90 | Self::Rotate270 => (height, width),
+            Self::Rotate180 | Self::FlipHorizontal | Self::FlipVertical => (width, height),
+        }
+    }
+
+    /// Maps a coordinate of the transformed grid back to the original
+    /// `width` by `height` grid
+    pub fn to_original(self, coord: GridCoord, width: usize, height: usize) -> GridCoord {
+        let GridCoord { x, y } = coord;
+        let (x, y) = match self {
+            Self::Transpose => (y, x),
+            Self::Rotate90 => (y, height - 1 - x),
+            Self::Rotate180 => (width - 1 - x, height - 1 - y),
+            Self::Rotate270 => (width - 1 - y, x),
+            Self::FlipHorizontal => (width - 1 - x, y),
+            Self::FlipVertical => (x, height - 1 - y),
+        };
+        GridCoord { x, y }
+    }
+}
+
+/// A borrowed rectangle of a [`Grid`], addressed from its own top-left corner
+pub struct SubGrid<'a, T> {
+    grid: &'a Grid<T>,
+    origin: GridCoord,
+    width: usize,
+    height: usize,
+}
+
+impl<'a, T> SubGrid<'a, T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, coord: GridCoord) -> bool {
+        coord.x < self.width && coord.y < self.height
+    }
 
-            row_strings.push(row_vec.join(" "));
+    /// Maps a coordinate of the view to the underlying grid
+    pub fn to_original(&self, coord: GridCoord) -> GridCoord {
+        GridCoord {
+            x: self.origin.x + coord.x,
+            y: self.origin.y + coord.y,
         }
+    }
+
+    pub fn get_cell_borrowed(&self, coord: GridCoord) -> Option<&'a T> {
+        if !self.in_bounds(coord) {
+            return None;
+        }
+        self.grid.get_cell_borrowed(self.to_original(coord))
+    }
 
-        write!(f, "{}", row_strings.join("\n"))
+    /// Every cell of the view in row-major order, with view coordinates
+    pub fn cells(&self) -> impl Iterator<Item = (GridCoord, &'a T)> + '_ {
+        (0..self.height)
+            .flat_map(move |y| (0..self.width).map(move |x| GridCoord { x, y }))
+            .filter_map(|coord| Some((coord, self.get_cell_borrowed(coord)?)))
+    }
+
+    /// Copies the view into a grid of its own
+    pub fn to_grid(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width: self.width,
+            height: self.height,
+            data: self.cells().map(|(_, cell)| cell.clone()).collect(),
+        }
     }
 }
 
-impl<T> Grid<T>
-where
-    T: Default + Clone,
-{
-    pub fn new(width: usize, height: usize) -> Self {
-        Self {
+/// Cells are written without separators when each fits in one character,
+/// otherwise right-aligned and separated by spaces
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cells = self
+            .data
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        let cell_width = cells.iter().map(|c| c.chars().count()).max().unwrap_or(0);
+        let separator = if cell_width > 1 { " " } else { "" };
+
+        for (y, row) in cells.chunks(self.width.max(1)).enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            let row = row
+                .iter()
+                .map(|cell| format!("{cell:>cell_width$}"))
+                .collect::<Vec<_>>();
+            write!(f, "{}", row.join(separator))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: Display> Debug for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\n{self}")
+    }
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from one line per row, converting each character with
+    /// `parse`. On failure `parse` returns what it expected, e.g. `"a digit"`.
+    pub fn parse_with<E: Into<String>>(
+        input: &str,
+        mut parse: impl FnMut(char) -> Result<T, E>,
+    ) -> ParseResult<Self> {
+        let width = input.lines().next().unwrap_or_default().chars().count();
+        let mut data = vec![];
+        let mut height = 0;
+
+        for (y, line) in input.lines().enumerate() {
+            let row_width = line.chars().count();
+            if row_width < width {
+                return Err(
+                    ParseError::at(line, "", format!("a row of {width} cells")).with_line(y + 1)
+                );
+            }
+
+            for (x, (i, c)) in line.char_indices().enumerate() {
+                let snippet = &line[i..i + c.len_utf8()];
+                if x == width {
+                    return Err(ParseError::at(
+                        line,
+                        &line[i..],
+                        format!("a row of {width} cells"),
+                    )
+                    .with_line(y + 1));
+                }
+                data.push(parse(c).map_err(|expected| {
+                    ParseError::at(line, snippet, expected).with_line(y + 1)
+                })?);
+            }
+            height += 1;
+        }
+
+        Ok(Self {
             width,
             height,
-            data: vec![T::default(); width * height],
-        }
+            data,
+        })
     }
 
     pub fn in_bounds(&self, coord: GridCoord) -> bool {
@@ -66,6 +263,86 @@ where
         Some(&self.data[cord.y * self.width + cord.x])
     }
 
+    /// Cells of row `y`, left to right
+    pub fn row(&self, y: usize) -> impl Iterator<Item = (GridCoord, &T)> + '_ {
+        let xs = if y < self.height { 0..self.width } else { 0..0 };
+        xs.map(move |x| self.cell((x, y).into()))
+    }
+
+    /// Cells of column `x`, top to bottom
+    pub fn column(&self, x: usize) -> impl Iterator<Item = (GridCoord, &T)> + '_ {
+        let ys = if x < self.width { 0..self.height } else { 0..0 };
+        ys.map(move |y| self.cell((x, y).into()))
+    }
+
+    /// Every cell in row-major order
+    pub fn cells(&self) -> impl Iterator<Item = (GridCoord, &T)> + '_ {
+        (0..self.height).flat_map(move |y| self.row(y))
+    }
+
+    /// The up to four orthogonally adjacent cells
+    pub fn neighbors4(&self, coord: GridCoord) -> impl Iterator<Item = (GridCoord, &T)> + '_ {
+        self.neighbors(coord, &Direction::CARDINAL)
+    }
+
+    /// The up to eight adjacent cells, including diagonals
+    pub fn neighbors8(&self, coord: GridCoord) -> impl Iterator<Item = (GridCoord, &T)> + '_ {
+        self.neighbors(coord, &Direction::ALL)
+    }
+
+    fn neighbors<'a>(
+        &'a self,
+        coord: GridCoord,
+        directions: &'static [Direction],
+    ) -> impl Iterator<Item = (GridCoord, &'a T)> + 'a {
+        directions
+            .iter()
+            .filter_map(move |&direction| coord.step(direction))
+            .filter(|&neighbor| self.in_bounds(neighbor))
+            .map(|neighbor| self.cell(neighbor))
+    }
+
+    /// The cells from `coord` (exclusive) to the edge of the grid in `direction`
+    pub fn ray(
+        &self,
+        coord: GridCoord,
+        direction: Direction,
+    ) -> impl Iterator<Item = (GridCoord, &T)> + '_ {
+        std::iter::successors(Some(coord), move |&current| current.step(direction))
+            .skip(1)
+            .take_while(|&current| self.in_bounds(current))
+            .map(|current| self.cell(current))
+    }
+
+    fn cell(&self, coord: GridCoord) -> (GridCoord, &T) {
+        (coord, &self.data[coord.y * self.width + coord.x])
+    }
+
+    /// A binary PGM (`P5`) image with one grey pixel per cell
+    pub fn to_pgm(&self, mut shade: impl FnMut(&T) -> u8) -> Vec<u8> {
+        let mut image = format!("P5\n{} {}\n255\n", self.width, self.height).into_bytes();
+        image.extend(self.data.iter().map(&mut shade));
+        image
+    }
+
+    /// A binary PPM (`P6`) image with one RGB pixel per cell
+    pub fn to_ppm(&self, mut color: impl FnMut(GridCoord, &T) -> [u8; 3]) -> Vec<u8> {
+        let mut image = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        image.extend(self.cells().flat_map(|(coord, cell)| color(coord, cell)));
+        image
+    }
+
+    /// A `width` by `height` view starting at `origin`, if it fits in the grid
+    pub fn view(&self, origin: GridCoord, width: usize, height: usize) -> Option<SubGrid<'_, T>> {
+        let fits = origin.x + width <= self.width && origin.y + height <= self.height;
+        fits.then_some(SubGrid {
+            grid: self,
+            origin,
+            width,
+            height,
+        })
+    }
+
     pub fn width(&self) -> usize {
         self.width
     }
@@ -74,3 +351,219 @@ where
         self.height
     }
 }
+
+impl<T: Clone> Grid<T> {
+    /// A copy of the grid with `transform` applied
+    pub fn transformed(&self, transform: Transform) -> Self {
+        let (width, height) = transform.dimensions(self.width, self.height);
+        let data = (0..height)
+            .flat_map(|y| (0..width).map(move |x| GridCoord { x, y }))
+            .map(|coord| {
+                let original = transform.to_original(coord, self.width, self.height);
+                self.data[original.y * self.width + original.x].clone()
+            })
+            .collect();
+
+        Self {
+            width,
+            height,
+            data,
+        }
+    }
+
+    pub fn transpose(&self) -> Self {
+        self.transformed(Transform::Transpose)
+    }
+
+    pub fn rotate90(&self) -> Self {
+        self.transformed(Transform::Rotate90)
+    }
+
+    pub fn rotate180(&self) -> Self {
+        self.transformed(Transform::Rotate180)
+    }
+
+    pub fn rotate270(&self) -> Self {
+        self.transformed(Transform::Rotate270)
+    }
+
+    pub fn flip_horizontal(&self) -> Self {
+        self.transformed(Transform::FlipHorizontal)
+    }
+
+    pub fn flip_vertical(&self) -> Self {
+        self.transformed(Transform::FlipVertical)
+    }
+
+    /// A grid with every cell set to `value`
+    pub fn filled(width: usize, height: usize, value: T) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![value; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T>
+where
+    T: Default + Clone,
+{
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![T::default(); width * height],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_with() {
+        let grid = Grid::parse_with("#.\n.#", |c| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => Err("`#` or `.`"),
+        })
+        .unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+        assert_eq!(grid.get_cell_borrowed((1, 1).into()), Some(&true));
+        assert_eq!(grid.get_cell_borrowed((1, 0).into()), Some(&false));
+    }
+
+    #[test]
+    fn test_ragged_rows() {
+        let digit = |c: char| c.to_digit(10).ok_or("a digit");
+
+        let err = Grid::parse_with("123\n45\n678", digit).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.expected, "a row of 3 cells");
+
+        let err = Grid::parse_with("123\n4567", digit).unwrap_err();
+        assert_eq!((err.line, err.column, err.snippet.as_str()), (2, 4, "7"));
+    }
+
+    fn sample() -> Grid<u32> {
+        Grid::parse_with("123\n456\n789", |c| c.to_digit(10).ok_or("a digit")).unwrap()
+    }
+
+    fn values<'a>(cells: impl Iterator<Item = (GridCoord, &'a u32)>) -> Vec<u32> {
+        cells.map(|(_, value)| *value).collect()
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = sample();
+
+        assert_eq!(values(grid.row(1)), vec![4, 5, 6]);
+        assert_eq!(values(grid.column(2)), vec![3, 6, 9]);
+        assert_eq!(values(grid.row(3)), vec![]);
+        assert_eq!(values(grid.cells()).len(), 9);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = sample();
+
+        assert_eq!(values(grid.neighbors4((1, 1).into())), vec![2, 4, 6, 8]);
+        assert_eq!(values(grid.neighbors4((0, 0).into())), vec![2, 4]);
+        assert_eq!(values(grid.neighbors8((0, 0).into())), vec![2, 4, 5]);
+        assert_eq!(
+            values(grid.neighbors8((1, 1).into())),
+            vec![1, 2, 3, 4, 6, 7, 8, 9]
+        );
+    }
+
+    #[test]
+    fn test_rays() {
+        let grid = sample();
+
+        let ray = grid
+            .ray((0, 1).into(), Direction::Right)
+            .collect::<Vec<_>>();
+        assert_eq!(ray, vec![((1, 1).into(), &5), ((2, 1).into(), &6)]);
+        assert_eq!(
+            values(grid.ray((2, 2).into(), Direction::UpLeft)),
+            vec![5, 1]
+        );
+        assert_eq!(values(grid.ray((0, 0).into(), Direction::Up)), vec![]);
+    }
+
+    #[test]
+    fn test_images() {
+        let grid = sample();
+
+        let pgm = grid.to_pgm(|&value| value as u8 * 10);
+        assert_eq!(&pgm[..11], b"P5\n3 3\n255\n");
+        assert_eq!(&pgm[11..], &[10, 20, 30, 40, 50, 60, 70, 80, 90]);
+
+        let ppm = grid.to_ppm(|coord, &value| [value as u8, coord.x as u8, coord.y as u8]);
+        assert_eq!(&ppm[..11], b"P6\n3 3\n255\n");
+        assert_eq!(&ppm[11 + 5 * 3..11 + 6 * 3], &[6, 2, 1]);
+    }
+
+    #[test]
+    fn test_transforms() {
+        let grid = sample();
+
+        assert_eq!(grid.transpose().to_string(), "147\n258\n369");
+        assert_eq!(grid.rotate90().to_string(), "741\n852\n963");
+        assert_eq!(grid.rotate180().to_string(), "987\n654\n321");
+        assert_eq!(grid.rotate270().to_string(), "369\n258\n147");
+        assert_eq!(grid.flip_horizontal().to_string(), "321\n654\n987");
+        assert_eq!(grid.flip_vertical().to_string(), "789\n456\n123");
+        assert!(grid.rotate90().rotate90().rotate90().rotate90() == grid);
+    }
+
+    #[test]
+    fn test_transform_coordinates() {
+        let grid = Grid::parse_with("1234\n5678", |c| c.to_digit(10).ok_or("a digit")).unwrap();
+        let transforms = [
+            Transform::Transpose,
+            Transform::Rotate90,
+            Transform::Rotate180,
+            Transform::Rotate270,
+            Transform::FlipHorizontal,
+            Transform::FlipVertical,
+        ];
+
+        for transform in transforms {
+            let transformed = grid.transformed(transform);
+            for (coord, value) in transformed.cells() {
+                let original = transform.to_original(coord, grid.width(), grid.height());
+                assert_eq!(
+                    grid.get_cell_borrowed(original),
+                    Some(value),
+                    "{transform:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_sub_grid() {
+        let grid = sample();
+        let view = grid.view((1, 1).into(), 2, 2).unwrap();
+
+        assert_eq!(view.get_cell_borrowed((0, 1).into()), Some(&8));
+        assert_eq!(view.get_cell_borrowed((2, 0).into()), None);
+        assert_eq!(view.to_original((1, 0).into()), (2, 1).into());
+        assert_eq!(view.to_grid().to_string(), "56\n89");
+        assert!(grid.view((2, 2).into(), 2, 1).is_none());
+    }
+
+    #[test]
+    fn test_display() {
+        let digits = Grid::parse_with("303\n255", |c| c.to_digit(10).ok_or("a digit")).unwrap();
+        assert_eq!(digits.to_string(), "303\n255");
+
+        let mut scores = Grid::<u32>::new(2, 2);
+        *scores.get_cell_mut((1, 0).into()).unwrap() = 12;
+        assert_eq!(scores.to_string(), " 0 12\n 0  0");
+    }
+}
@@ -0,0 +1,60 @@
+//! PGM/PPM renderings of the forest for demos and debugging.
+
+use crate::grid::{Grid, GridCoord};
+
+/// Tree heights from black (0) to white (9)
+pub fn height_map(grid: &Grid<usize>) -> Vec<u8> {
+    grid.to_pgm(|&height| (height.min(9) * 255 / 9) as u8)
+}
+
+/// Visible trees in white, hidden ones in black
+pub fn visibility_image(mask: &Grid<bool>) -> Vec<u8> {
+    mask.to_pgm(|&visible| if visible { 255 } else { 0 })
+}
+
+/// Scenic scores from blue (lowest) to red (highest), with the best tree in yellow
+pub fn scenic_heatmap(scores: &Grid<u64>) -> Vec<u8> {
+    let best = scores.cells().max_by_key(|&(_, &score)| score);
+    let (best_coord, max) = best.map_or((None, 0), |(coord, &score)| (Some(coord), score));
+
+    scores.to_ppm(|coord: GridCoord, &score| {
+        if Some(coord) == best_coord {
+            return [255, 255, 0];
+        }
+        // Scores can be close to u64::MAX, so scale them without overflowing
+        let heat = (u128::from(score) * 255)
+            .checked_div(u128::from(max))
+            .unwrap_or(0) as u8;
+        [heat, 0, 255 - heat]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_grid, scenic_scores};
+
+    #[test]
+    fn test_scenic_heatmap() {
+        let grid = parse_grid(include_str!("initial_input.txt")).unwrap();
+        let image = scenic_heatmap(&scenic_scores(&grid));
+
+        let header = b"P6\n5 5\n255\n";
+        assert_eq!(&image[..header.len()], header);
+
+        // The best tree, (2, 3) with a score of 8, is highlighted
+        let pixel = header.len() + (3 * 5 + 2) * 3;
+        assert_eq!(&image[pixel..pixel + 3], &[255, 255, 0]);
+        assert_eq!(&image[header.len()..header.len() + 3], &[0, 0, 255]);
+    }
+
+    #[test]
+    fn test_scenic_heatmap_large_scores() {
+        let mut scores = Grid::filled(2, 1, u64::MAX / 2);
+        *scores.get_cell_mut((1, 0).into()).unwrap() = u64::MAX;
+        let image = scenic_heatmap(&scores);
+
+        let header = b"P6\n2 1\n255\n";
+        assert_eq!(&image[header.len()..], &[127, 0, 128, 255, 255, 0]);
+    }
+}
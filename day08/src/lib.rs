@@ -1,79 +1,158 @@
-mod grid;
-use std::error::Error;
+pub mod grid;
+pub mod images;
+
+use aoc_common::{
+    parse::{ParseError, ParseResult},
+    Answer, Solution,
+};
+use grid::{Direction, Grid, GridCoord};
+
+pub fn parse_grid(input: &str) -> ParseResult<Grid<usize>> {
+    Grid::parse_with(input, |c| {
+        c.to_digit(10)
+            .map(|height| height as usize)
+            .ok_or("a tree height digit")
+    })
+}
 
-use grid::{Grid, GridCoord};
+pub fn get_scenic_score(grid: &Grid<usize>, coord: GridCoord) -> Option<i32> {
+    let coord_height = grid.get_cell_borrowed(coord)?;
+
+    let trees_visible_in_dir = Direction::CARDINAL.map(|direction| {
+        let mut visible = 0;
+        // Stop at the first tree at least as tall as the current one
+        for (_, height) in grid.ray(coord, direction) {
+            visible += 1;
+            if height >= coord_height {
+                break;
+            }
+        }
+        visible
+    });
 
-pub fn parse_grid(input: &str) -> Grid<usize> {
-    let width = input.lines().next().unwrap().len();
-    let height = input.lines().count();
+    Some(trees_visible_in_dir.iter().product())
+}
 
-    let mut grid = Grid::new(width, height);
-    for (y, line) in input.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            assert!(c.is_ascii_digit());
-            *grid.get_cell_mut((x, y).into()).unwrap() = c as usize - '0' as usize;
-        }
+/// Marks every tree visible from outside the grid, returning the mask and how
+/// many trees are visible. Each row and column is swept once from both ends,
+/// keeping the tallest tree seen so far.
+pub fn visibility_mask(grid: &Grid<usize>) -> (Grid<bool>, usize) {
+    let (width, height) = (grid.width(), grid.height());
+    let mut mask = Grid::new(width, height);
+
+    for y in 0..height {
+        let row = (0..width).map(|x| GridCoord { x, y });
+        mark_visible(grid, &mut mask, row.clone());
+        mark_visible(grid, &mut mask, row.rev());
+    }
+    for x in 0..width {
+        let column = (0..height).map(|y| GridCoord { x, y });
+        mark_visible(grid, &mut mask, column.clone());
+        mark_visible(grid, &mut mask, column.rev());
     }
 
-    grid
+    let count = mask.cells().filter(|(_, &visible)| visible).count();
+    (mask, count)
 }
 
-pub fn get_scenic_score(grid: &Grid<usize>, coord: GridCoord) -> Option<i32> {
-    let coord_height = grid.get_cell_borrowed(coord).unwrap();
-    let dirs: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
-    let mut trees_visible_in_dir: Vec<i32> = vec![];
-
-    for dir in dirs.iter() {
-        let mut current_coord = coord;
-        let mut visible_in_cur_dir = 0;
-
-        loop {
-            if current_coord.y.checked_add_signed(dir.1).is_none()
-                | current_coord.x.checked_add_signed(dir.0).is_none()
-            {
-                break;
+fn mark_visible(
+    grid: &Grid<usize>,
+    mask: &mut Grid<bool>,
+    line_of_sight: impl Iterator<Item = GridCoord>,
+) {
+    let mut tallest = None;
+    for coord in line_of_sight {
+        let height = grid.get_cell_borrowed(coord).copied();
+        if height > tallest {
+            if let Some(visible) = mask.get_cell_mut(coord) {
+                *visible = true;
             }
+            tallest = height;
+        }
+    }
+}
 
-            current_coord = GridCoord {
-                x: current_coord.x.checked_add_signed(dir.0).unwrap(),
-                y: current_coord.y.checked_add_signed(dir.1).unwrap(),
-            };
+/// The scenic score of every tree, using one monotonic stack per row or
+/// column and direction instead of walking rays from each tree
+pub fn scenic_scores(grid: &Grid<usize>) -> Grid<u64> {
+    let (width, height) = (grid.width(), grid.height());
+    let mut scores = Grid::filled(width, height, 1);
 
-            if !grid.in_bounds(current_coord) {
-                break;
-            }
-            visible_in_cur_dir += 1;
+    for y in 0..height {
+        let row = (0..width).map(|x| GridCoord { x, y });
+        multiply_viewing_distances(grid, &mut scores, row.clone());
+        multiply_viewing_distances(grid, &mut scores, row.rev());
+    }
+    for x in 0..width {
+        let column = (0..height).map(|y| GridCoord { x, y });
+        multiply_viewing_distances(grid, &mut scores, column.clone());
+        multiply_viewing_distances(grid, &mut scores, column.rev());
+    }
 
-            // Break if we find a tree taller than the current coord
-            let cur_height = grid.get_cell_borrowed(current_coord).unwrap();
-            if cur_height >= coord_height {
-                break;
-            }
+    scores
+}
+
+/// Multiplies each score by how far that tree can see back along `line`
+fn multiply_viewing_distances(
+    grid: &Grid<usize>,
+    scores: &mut Grid<u64>,
+    line: impl Iterator<Item = GridCoord>,
+) {
+    // Positions and heights of trees not yet hidden behind a taller one,
+    // never increasing in height from bottom to top
+    let mut stack: Vec<(usize, usize)> = vec![];
+
+    for (position, coord) in line.enumerate() {
+        let Some(&height) = grid.get_cell_borrowed(coord) else {
+            continue;
+        };
+        while stack.last().is_some_and(|&(_, other)| other < height) {
+            stack.pop();
         }
 
-        trees_visible_in_dir.push(visible_in_cur_dir);
+        let distance = stack
+            .last()
+            .map_or(position, |&(blocker, _)| position - blocker);
+        if let Some(score) = scores.get_cell_mut(coord) {
+            *score *= distance as u64;
+        }
+        stack.push((position, height));
     }
+}
 
-    Some(trees_visible_in_dir.iter().product())
+fn max_scenic_score(grid: &Grid<usize>) -> u64 {
+    scenic_scores(grid)
+        .cells()
+        .map(|(_, &score)| score)
+        .max()
+        .unwrap_or(0)
 }
 
-fn main(grid: &Grid<usize>) -> i32 {
-    let all_coords = (0..grid.height()).into_iter().flat_map(|y| {
-        (0..grid.width())
-            .into_iter()
-            .map(move |x| GridCoord { x, y })
-    });
+pub struct Day08;
 
-    let max_scenic_score = all_coords
-        .map(|coord| get_scenic_score(grid, coord))
-        .max()
-        .unwrap();
+impl Solution for Day08 {
+    const DAY: u8 = 8;
+
+    type Parsed = Grid<usize>;
 
-    max_scenic_score.unwrap()
+    fn parse(input: &str) -> color_eyre::Result<Self::Parsed> {
+        parse_grid(input).map_err(ParseError::into_report)
+    }
+
+    fn part1(grid: &Self::Parsed) -> color_eyre::Result<Answer> {
+        let (_, visible) = visibility_mask(grid);
+        Ok(visible.into())
+    }
+
+    fn part2(grid: &Self::Parsed) -> color_eyre::Result<Answer> {
+        Ok(max_scenic_score(grid).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use crate::grid::GridCoord;
 
     use super::*;
@@ -81,7 +160,7 @@ mod tests {
     #[test]
     fn test_parse_grid() {
         let input = include_str!("initial_input.txt");
-        let grid = parse_grid(input);
+        let grid = parse_grid(input).unwrap();
         assert!(grid.get_cell_borrowed((0, 0).into()).unwrap() == &3);
         dbg!(grid);
     }
@@ -89,8 +168,8 @@ mod tests {
     #[test]
     fn test_main() {
         let input = include_str!("full_input.txt");
-        let grid = parse_grid(input);
-        let max_scenic_score = main(&grid);
+        let grid = parse_grid(input).unwrap();
+        let max_scenic_score = max_scenic_score(&grid);
         dbg!(max_scenic_score);
     }
 
@@ -101,8 +180,66 @@ mod tests {
 65332
 33549
 35390";
-        let grid = parse_grid(grid_str);
+        let grid = parse_grid(grid_str).unwrap();
         let scenic_score = get_scenic_score(&grid, GridCoord { x: 2, y: 1 });
         assert!(scenic_score == Some(4));
     }
+
+    #[test]
+    fn test_visibility_mask() {
+        let grid = parse_grid(include_str!("initial_input.txt")).unwrap();
+        let (mask, count) = visibility_mask(&grid);
+
+        assert_eq!(count, 21);
+        let interior = (1..4)
+            .map(|y| {
+                mask.row(y)
+                    .skip(1)
+                    .take(3)
+                    .map(|(_, &visible)| if visible { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(interior, vec!["##.", "#.#", ".#."]);
+    }
+
+    #[test]
+    fn test_scenic_scores() {
+        let grid = parse_grid(include_str!("initial_input.txt")).unwrap();
+        let scores = scenic_scores(&grid);
+
+        assert_eq!(scores.get_cell_borrowed((2, 1).into()), Some(&4));
+        assert_eq!(scores.get_cell_borrowed((2, 3).into()), Some(&8));
+        assert_eq!(scores.get_cell_borrowed((0, 0).into()), Some(&0));
+    }
+
+    fn grids() -> impl Strategy<Value = Grid<usize>> {
+        (1..8usize, 1..8usize).prop_flat_map(|(width, height)| {
+            prop::collection::vec(prop::collection::vec(0..10u32, width), height).prop_map(|rows| {
+                let input = rows
+                    .iter()
+                    .map(|row| row.iter().map(u32::to_string).collect::<String>())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                parse_grid(&input).unwrap()
+            })
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn scenic_scores_match_naive(grid in grids()) {
+            let scores = scenic_scores(&grid);
+            for (coord, &score) in scores.cells() {
+                let naive = get_scenic_score(&grid, coord).unwrap();
+                prop_assert_eq!(score, naive as u64, "at {:?}", coord);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid_height() {
+        let err = parse_grid("303\n2a5").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+    }
 }
@@ -1,3 +1,5 @@
+use aoc_common::parse::{parse_lines, ParseError, ParseResult};
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum Instruction {
     Down,
@@ -6,36 +8,35 @@ pub enum Instruction {
     Up,
 }
 
-pub fn parse_line(line: &str) -> Vec<Instruction> {
-    let segments: Vec<&str> = line.split(' ').collect();
-    let dir_char = segments[0].chars().nth(0).unwrap();
+pub fn parse_line(line: &str) -> ParseResult<Vec<Instruction>> {
+    let Some((dir_str, count_str)) = line.split_once(' ') else {
+        return Err(ParseError::at(line, "", "a direction and a step count"));
+    };
 
-    let direction = match dir_char {
-        'U' => Instruction::Up,
-        'D' => Instruction::Down,
-        'L' => Instruction::Left,
-        'R' => Instruction::Right,
-        _ => panic!("Invalid direction"),
+    let direction = match dir_str {
+        "U" => Instruction::Up,
+        "D" => Instruction::Down,
+        "L" => Instruction::Left,
+        "R" => Instruction::Right,
+        _ => return Err(ParseError::at(line, dir_str, "one of U, D, L or R")),
     };
 
-    let count = segments[1].parse::<i32>().unwrap();
+    let count = count_str
+        .parse::<u32>()
+        .map_err(|_| ParseError::at(line, count_str, "a step count"))?;
 
     let mut instructions = Vec::new();
 
     for _ in 0..count {
-        instructions.push(direction.clone());
+        instructions.push(direction);
     }
 
-    instructions
+    Ok(instructions)
 }
 
-pub fn parse_instructions(str: &str) -> Vec<Instruction> {
-    let mut instructions = Vec::new();
-    for line in str.lines() {
-        instructions.push(parse_line(line));
-    }
-    let instructions_flattened: Vec<Instruction> =
-        instructions.iter().flatten().map(|i| i.clone()).collect();
+pub fn parse_instructions(str: &str) -> ParseResult<Vec<Instruction>> {
+    let instructions = parse_lines(str, parse_line)?;
+    let instructions_flattened: Vec<Instruction> = instructions.into_iter().flatten().collect();
 
-    instructions_flattened.clone()
+    Ok(instructions_flattened)
 }
@@ -1,58 +1,98 @@
+use std::collections::HashSet;
+
+use aoc_common::{parse::ParseError, Answer, Solution};
 use grid::GridPos;
 use instruction_parsing::Instruction;
-use itertools::Itertools;
 
 pub mod grid;
 pub mod instruction_parsing;
 
-fn main(instructions: Vec<Instruction>) -> usize {
-    let mut knots = [GridPos { x: 0, y: 0 }; 10];
-    let mut tail_positions_visited = vec![knots[0].clone()];
-
-    for i in instructions {
-        let mut head_position = knots[0];
-
-        match i {
-            Instruction::Up => {
-                head_position.y += 1;
-            }
-            Instruction::Down => {
-                head_position.y -= 1;
-            }
-            Instruction::Left => {
-                head_position.x -= 1;
-            }
-            Instruction::Right => {
-                head_position.x += 1;
-            }
+/// A rope of knots dragged by its head, remembering where every knot has been
+#[derive(Debug, Clone)]
+pub struct Rope {
+    knots: Vec<GridPos>,
+    visited: Vec<HashSet<GridPos>>,
+}
+
+impl Rope {
+    /// A rope of `knot_count` knots, all starting at the origin. The head
+    /// counts as a knot, so the puzzle's two parts use 2 and 10.
+    ///
+    /// # Panics
+    ///
+    /// If `knot_count` is 0, since a rope without a head cannot move.
+    pub fn new(knot_count: usize) -> Self {
+        assert!(knot_count > 0, "a rope needs at least one knot");
+
+        let start = GridPos { x: 0, y: 0 };
+        Self {
+            knots: vec![start; knot_count],
+            visited: vec![HashSet::from([start]); knot_count],
+        }
+    }
+
+    pub fn knots(&self) -> &[GridPos] {
+        &self.knots
+    }
+
+    /// Every position knot `knot` has occupied, where 0 is the head
+    pub fn visited(&self, knot: usize) -> Option<&HashSet<GridPos>> {
+        self.visited.get(knot)
+    }
+
+    pub fn tail_visited(&self) -> &HashSet<GridPos> {
+        &self.visited[self.visited.len() - 1]
+    }
+
+    pub fn simulate(&mut self, instructions: &[Instruction]) {
+        for &instruction in instructions {
+            self.step(instruction);
+        }
+    }
+
+    /// Moves the head one step and lets every following knot catch up
+    pub fn step(&mut self, instruction: Instruction) {
+        let head = &mut self.knots[0];
+        match instruction {
+            Instruction::Up => head.y += 1,
+            Instruction::Down => head.y -= 1,
+            Instruction::Left => head.x -= 1,
+            Instruction::Right => head.x += 1,
         }
-        knots[0] = head_position;
-
-        // Process each knot after the head
-        for i in 1..knots.len() {
-            // 1 to skip the head
-            let diff = knots[i - 1] - knots[i];
-            let (dx, dy) = update_position(diff);
-
-            knots[i] = GridPos {
-                x: knots[i].x + dx,
-                y: knots[i].y + dy,
-            };
-
-            if i == knots.len() - 1 {
-                tail_positions_visited.push(knots[i]);
-            }
+        self.visited[0].insert(self.knots[0]);
+
+        for i in 1..self.knots.len() {
+            let (dx, dy) = update_position(self.knots[i - 1] - self.knots[i]);
+            self.knots[i] = self.knots[i] + GridPos { x: dx, y: dy };
+            self.visited[i].insert(self.knots[i]);
         }
     }
+}
+
+fn tail_positions_visited(instructions: &[Instruction], knot_count: usize) -> usize {
+    let mut rope = Rope::new(knot_count);
+    rope.simulate(instructions);
+    rope.tail_visited().len()
+}
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u8 = 9;
+
+    type Parsed = Vec<Instruction>;
+
+    fn parse(input: &str) -> color_eyre::Result<Self::Parsed> {
+        instruction_parsing::parse_instructions(input).map_err(ParseError::into_report)
+    }
 
-    // Get unique positions visited by the tail with a HashSet
-    let unique_tail_positions: Vec<&GridPos> = tail_positions_visited.iter().unique().collect();
+    fn part1(instructions: &Self::Parsed) -> color_eyre::Result<Answer> {
+        Ok(tail_positions_visited(instructions, 2).into())
+    }
 
-    println!(
-        "The tail visited {} unique positions",
-        unique_tail_positions.len()
-    );
-    unique_tail_positions.len()
+    fn part2(instructions: &Self::Parsed) -> color_eyre::Result<Answer> {
+        Ok(tail_positions_visited(instructions, 10).into())
+    }
 }
 
 fn update_position(position_delta: GridPos) -> (i32, i32) {
@@ -90,33 +130,68 @@ fn update_position(position_delta: GridPos) -> (i32, i32) {
 }
 #[cfg(test)]
 mod tests {
-    use crate::instruction_parsing::{parse_line, Instruction};
+    use crate::instruction_parsing::{parse_instructions, parse_line, Instruction};
 
     use super::*;
     #[test]
     fn test_full_input() {
         let input = include_str!("full_input.txt");
-        let lines = input.lines();
-        let instructions = lines.map(|l| parse_line(l)).flatten().collect();
+        let instructions = parse_instructions(input).unwrap();
 
-        let positions_visited = main(instructions);
+        assert_eq!(tail_positions_visited(&instructions, 2), 6271);
+        assert_eq!(tail_positions_visited(&instructions, 10), 2458);
     }
     #[test]
     fn test_starting_input() {
         let input = include_str!("sample_input.txt");
-        let lines = input.lines();
-        let instructions = lines.map(|l| parse_line(l)).flatten().collect();
+        let instructions = parse_instructions(input).unwrap();
 
-        let positions_visited = main(instructions);
+        let positions_visited = tail_positions_visited(&instructions, 2);
         assert_eq!(positions_visited, 13);
     }
 
+    #[test]
+    fn test_larger_example() {
+        let instructions =
+            parse_instructions("R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20").unwrap();
+
+        let mut rope = Rope::new(10);
+        rope.simulate(&instructions);
+        assert_eq!(rope.tail_visited().len(), 36);
+        assert_eq!(rope.knots()[0], GridPos { x: -11, y: 15 });
+    }
+
+    #[test]
+    fn test_any_knot() {
+        let instructions = parse_instructions(include_str!("sample_input.txt")).unwrap();
+
+        let mut short = Rope::new(2);
+        short.simulate(&instructions);
+        let mut long = Rope::new(10);
+        long.simulate(&instructions);
+
+        // The knot behind the head moves the same no matter what follows it
+        assert_eq!(long.visited(1), short.visited(1));
+        assert_eq!(long.visited(9).map(HashSet::len), Some(1));
+        assert_eq!(long.visited(10), None);
+    }
+
     #[test]
     fn test_parse_line() {
         let line = "U 10";
-        let instructions = parse_line(line);
+        let instructions = parse_line(line).unwrap();
         assert_eq!(instructions.len(), 10);
         assert_eq!(instructions[0], Instruction::Up);
         assert_eq!(instructions[1], Instruction::Up);
     }
+
+    #[test]
+    fn test_parse_invalid_direction() {
+        let err = parse_instructions("U 1\nX 4").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.snippet, "X");
+
+        let err = parse_line("U").unwrap_err();
+        assert_eq!(err.column, 2);
+    }
 }
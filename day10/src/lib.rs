@@ -1,19 +1,27 @@
-#[derive(Clone, Copy)]
+use aoc_common::{
+    parse::{parse_lines, ParseError, ParseResult},
+    Answer, Solution,
+};
+
+#[derive(Debug, Clone, Copy)]
 pub enum Instruction {
     Noop,
     Addx(i32),
 }
 
 impl Instruction {
-    pub fn parse(i: &str) -> Instruction {
-        let instruction_type = i.split(' ').nth(0).unwrap();
-
-        if instruction_type == "addx" {
-            let amount = i.split(' ').nth(1).unwrap().parse::<i32>().unwrap();
-            return Instruction::Addx(amount);
+    pub fn parse(i: &str) -> ParseResult<Instruction> {
+        let (instruction_type, argument) = i.split_once(' ').unwrap_or((i, ""));
+
+        match instruction_type {
+            "noop" if argument.is_empty() => Ok(Instruction::Noop),
+            "noop" => Err(ParseError::at(i, argument, "no argument to noop")),
+            "addx" => argument
+                .parse::<i32>()
+                .map(Instruction::Addx)
+                .map_err(|_| ParseError::at(i, argument, "an amount to add")),
+            _ => Err(ParseError::at(i, instruction_type, "`noop` or `addx`")),
         }
-
-        Instruction::Noop
     }
 
     pub fn cycle_count(&self) -> i32 {
@@ -24,7 +32,7 @@ impl Instruction {
     }
 }
 
-struct InstructionHistory {
+pub struct InstructionHistory {
     instructions: Vec<Instruction>,
 }
 
@@ -49,27 +57,41 @@ impl InstructionHistory {
     }
 }
 
-pub fn main(input: &str) {
-    let instructions = input
-        .lines()
-        .map(|i| Instruction::parse(i))
-        .collect::<Vec<_>>();
+pub struct Day10;
 
-    let history = InstructionHistory {
-        instructions: instructions.clone(),
-    };
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Parsed = InstructionHistory;
+
+    fn parse(input: &str) -> color_eyre::Result<Self::Parsed> {
+        let instructions =
+            parse_lines(input, Instruction::parse).map_err(ParseError::into_report)?;
+        Ok(InstructionHistory { instructions })
+    }
+
+    fn part1(history: &Self::Parsed) -> color_eyre::Result<Answer> {
+        let signal_strength = (20..=220)
+            .step_by(40)
+            .map(|cycle| cycle * history.register_value_during_cycle(&cycle))
+            .sum::<i32>();
+
+        Ok(signal_strength.into())
+    }
+
+    fn part2(history: &Self::Parsed) -> color_eyre::Result<Answer> {
+        Ok(render_crt(history).trim_end().into())
+    }
+}
 
+fn render_crt(history: &InstructionHistory) -> String {
     let mut pixels = vec![];
 
     for cycle_count in 1..=240 {
         let register_value = history.register_value_during_cycle(&cycle_count);
         let crt_position = (cycle_count - 1) % 40;
-        let sprite_interval = crt_position - 2..crt_position + 2;
-
-        println!("Register value: {:?}", register_value);
-        println!("CRT position: {:?}", crt_position);
-        println!("Sprite interval: {:?}", sprite_interval);
-        println!("\n");
+        // The sprite is three pixels wide and centred on X
+        let sprite_interval = crt_position - 1..crt_position + 2;
 
         if sprite_interval.contains(&register_value) {
             pixels.push("#");
@@ -77,29 +99,55 @@ pub fn main(input: &str) {
             pixels.push(".");
         }
 
-        if cycle_count.clone() % 40 == 0 {
+        if cycle_count % 40 == 0 {
             pixels.push("\n");
         }
     }
 
-    for pixel in pixels {
-        print!("{}", pixel);
-    }
+    pixels.concat()
 }
 
+#[cfg(test)]
 mod tests {
-    use crate::main;
+    use aoc_common::{Answer, Solution};
+
+    use crate::Day10;
 
     #[test]
     fn test_main() {
         let input = include_str!("input.txt");
-        main(input);
+        let history = Day10::parse(input).unwrap();
+        println!("{}", Day10::part2(&history).unwrap());
     }
 
     #[test]
     fn test_smaller_sample() {
         let input = include_str!("small_sample.txt");
-        main(input);
+        let history = Day10::parse(input).unwrap();
+        assert_eq!(Day10::part1(&history).unwrap(), Answer::Number(13140));
+        assert_eq!(
+            Day10::part2(&history).unwrap(),
+            Answer::from(
+                "##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######....."
+            )
+        );
+    }
+
+    #[test]
+    fn test_sprite_is_three_pixels_wide() {
+        // X stays 1, so the sprite covers pixels 0, 1 and 2 of every row
+        let history = Day10::parse("noop").unwrap();
+        let row = format!("###{}", ".".repeat(37));
+        let expected = vec![row; 6].join("\n");
+        assert_eq!(
+            Day10::part2(&history).unwrap(),
+            Answer::from(expected.as_str())
+        );
     }
 
     #[test]
@@ -107,18 +155,25 @@ mod tests {
         let input = include_str!("sample_input.txt");
         let instructions = input
             .lines()
-            .map(|i| super::Instruction::parse(i))
+            .map(|line| super::Instruction::parse(line).unwrap())
             .collect::<Vec<_>>();
 
         let n_cycles = instructions.iter().map(|i| i.cycle_count()).sum::<i32>();
         assert_eq!(n_cycles, 5);
 
-        let history = super::InstructionHistory {
-            instructions: instructions,
-        };
+        let history = super::InstructionHistory { instructions };
         assert_eq!(history.register_value_during_cycle(&3), 1);
         assert_eq!(history.register_value_during_cycle(&4), 4);
         assert_eq!(history.register_value_during_cycle(&5), 4);
         assert_eq!(history.register_value_during_cycle(&6), -1);
     }
+
+    #[test]
+    fn test_parse_unknown_instruction() {
+        let err = super::Instruction::parse("addy 3").unwrap_err();
+        assert_eq!((err.column, err.snippet.as_str()), (1, "addy"));
+
+        let err = super::Instruction::parse("addx").unwrap_err();
+        assert_eq!(err.column, 5);
+    }
 }